@@ -1,13 +1,59 @@
-use crate::lexer::{Token, TokenType};
-use crate::parser::VarTypes;
+use crate::lexer::{Segment, Token, TokenType};
+use crate::error::{Diagnostic, ErrorKind};
+use crate::parser::{str_to_types, VarTypes};
+use crate::bigint::BigInt;
+
+/*
+precedence table, higher binds tighter
+
+    11  a[i] p.x p.f()      postfix
+    10  ( ) literals variables calls arrays
+    9   **                  right
+    8   -x +x               prefix
+    7   x as T              left
+    6   * / %               left
+    5   + -                 left
+    4   == != < > <= >=     left
+    3   not x               prefix
+    2   and                 left
+    1   or                  left
+
+binary operators are parsed by precedence climbing, the right side of a left associative
+operator only takes operators that bind tighter so `10 - 3 - 2` is `(10 - 3) - 2`, the right side
+of `**` takes another `**` so `2 ** 3 ** 2` is `2 ** (3 ** 2)`. `-2 ** 2` is `-(2 ** 2)`, `-x as u8` is
+`(-x) as u8` and the right side of `as` is a type
+*/
+const OR_PRECEDENCE: u8 = 1;
+const AND_PRECEDENCE: u8 = 2;
+const NOT_PRECEDENCE: u8 = 3;
+const COMPARISON_PRECEDENCE: u8 = 4;
+const EXPRESSION_PRECEDENCE: u8 = 5;
+const TERM_PRECEDENCE: u8 = 6;
+const CAST_PRECEDENCE: u8 = 7;
+const NEGATIVE_PRECEDENCE: u8 = 8;
+const POWER_PRECEDENCE: u8 = 9;
+
+fn binary_precedence(token: &Token) -> Option<u8> {
+    match token.token_type {
+        TokenType::Or => Some(OR_PRECEDENCE),
+        TokenType::And => Some(AND_PRECEDENCE),
+        TokenType::As => Some(CAST_PRECEDENCE),
+        TokenType::ComparisonOperation => Some(COMPARISON_PRECEDENCE),
+        TokenType::MathOperation => match &*token.value {
+            "+" | "-" => Some(EXPRESSION_PRECEDENCE),
+            "*" | "/" | "%" => Some(TERM_PRECEDENCE),
+            "**" => Some(POWER_PRECEDENCE),
+            _ => None
+        },
+        _ => None
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Math {
     given: Vec<Token>,
     ind: i32,
     current: Token,
-    terms: Vec<String>,
-    expression: Vec<String>
 }
 
 impl Math {
@@ -16,97 +62,386 @@ impl Math {
             given: vec![],
             ind: -1,
             current: Token::new(TokenType::NullForParser, "".to_string()),
-            terms: vec!["*".to_string(), "/".to_string()],
-            expression: vec!["+".to_string(), "-".to_string()]
         }
     }
     fn next(&mut self) -> bool {
         self.ind += 1;
         if self.ind >= self.given.len() as i32 {
+            // keeps the position of the last token so errors at the end still point somewhere
+            let (x, y, length) = (self.current.x, self.current.y, self.current.length);
+            self.current = Token::new(TokenType::NullForParser, "".to_string());
+            self.current.set_xy(x, y);
+            self.current.length = length;
             false
         } else {
             self.current = self.given[self.ind as usize].clone();
             true
         }
     }
-    fn factor(&mut self) -> Expr {
-        if self.current.token_type == TokenType::String {
-            Expr::String(self.current.value.clone())
-        } else if self.current.token_type == TokenType::Boolean {
-            if self.current.value == "true" {
-                Expr::Bool(true)
-            } else if self.current.value == "false" {
-                Expr::Bool(false)
+    fn error(&self, message: String, token: &Token) -> Diagnostic {
+        Diagnostic::at_token(ErrorKind::Syntax, message, token)
+    }
+    fn arguments(&mut self, close: TokenType) -> Result<Vec<Expr>, Diagnostic> {
+        // comma separated expressions after an opening bracket, up to the closing one
+        let mut arguments = vec![];
+        self.next();
+        if self.current.token_type == close {
+            self.next();
+            return Ok(arguments)
+        }
+        loop {
+            arguments.push(self.expression(OR_PRECEDENCE)?);
+            if self.current.token_type == close {
+                self.next();
+                return Ok(arguments)
+            } else if self.current.token_type != TokenType::SeperatorComma {
+                let close_symbol = if close == TokenType::BracketClose { "]" } else { ")" };
+                return Err(self.error(format!("Expected ',' or '{}' got '{:?}' instead", close_symbol, self.current.token_type), &self.current))
+            }
+            self.next();
+        }
+    }
+    fn factor(&mut self) -> Result<Expr, Diagnostic> {
+        // indexing and fields bind tighter than anything, `-a[0].x` is `-((a[0]).x)`
+        let mut value = self.primary()?;
+        loop {
+            if self.current.token_type == TokenType::BracketOpen {
+                let bracket = self.current.clone();
+                self.next();
+                let index = self.expression(OR_PRECEDENCE)?;
+                if self.current.token_type != TokenType::BracketClose {
+                    return Err(self.error("unclosed Bracket".to_string(), &bracket))
+                }
+                self.next();
+                value = Expr::Index(Box::new(value), Box::new(index), bracket);
+            } else if self.current.token_type == TokenType::DirectMemberSelection {
+                self.next();
+                if self.current.token_type != TokenType::Identifier {
+                    return Err(self.error(format!("Expected a field name got '{:?}' instead", self.current.token_type), &self.current))
+                }
+                let field = self.current.clone();
+                self.next();
+                if self.current.token_type == TokenType::ParenthesisOpen {
+                    let arguments = self.arguments(TokenType::ParenthesisClose)?;
+                    value = Expr::MethodCall(Box::new(value), field, arguments);
+                } else {
+                    value = Expr::Field(Box::new(value), field);
+                }
             } else {
-                panic!("How? {:?}", self.current)
+                return Ok(value)
             }
-        } else if self.current.token_type == TokenType::FloatingPoint {
-            Expr::Float(self.current.value.parse::<f64>().unwrap())
-        } else if self.current.token_type == TokenType::Integer {
-            Expr::Integer(self.current.value.parse::<i128>().unwrap())
-        } else {
-            panic!("Invalid type {:?}", self.current)
         }
     }
-    fn term(&mut self) -> Expr {
-        let mut left = self.factor();
+    fn primary(&mut self) -> Result<Expr, Diagnostic> {
+        let token = self.current.clone();
+        match token.token_type {
+            TokenType::String if !token.segments.is_empty() => {
+                // `"total: {count * 2}"`, the text and the expressions are joined when it is evaluated
+                self.next();
+                let mut parts = vec![];
+                for segment in &token.segments {
+                    match segment {
+                        Segment::Text(text) if text.is_empty() => {},
+                        Segment::Text(text) => parts.push(Expr::String(text.clone(), token.clone())),
+                        Segment::Code(tokens) => parts.push(Math::new().parse(tokens.clone())?)
+                    }
+                }
+                Ok(Expr::Interpolation(parts, token))
+            },
+            TokenType::String => {
+                self.next();
+                Ok(Expr::String(token.value.clone(), token))
+            },
+            TokenType::Character => {
+                self.next();
+                Ok(Expr::Char(token.value.chars().next().unwrap_or_default(), token))
+            },
+            TokenType::Boolean => {
+                self.next();
+                Ok(Expr::Bool(token.value == "true", token))
+            },
+            TokenType::FloatingPoint => {
+                self.next();
+                Ok(Expr::Float(token.value.parse::<f64>().unwrap(), token))
+            },
+            TokenType::Integer => {
+                self.next();
+                // literals too large for an int are a bigint
+                match token.value.parse::<i128>() {
+                    Ok(value) => Ok(Expr::Integer(value, token)),
+                    Err(_) => match BigInt::parse(&token.value) {
+                        Some(value) => Ok(Expr::BigInteger(value, token)),
+                        None => Err(self.error(format!("'{}' isn't an integer", token.value), &token))
+                    }
+                }
+            },
+            TokenType::Identifier => {
+                self.next();
+                if self.current.token_type != TokenType::ParenthesisOpen {
+                    return Ok(Expr::Variable(token))
+                }
+                // function call, arguments are separated by commas
+                let arguments = self.arguments(TokenType::ParenthesisClose)?;
+                Ok(Expr::Call(token, arguments))
+            },
+            TokenType::BracketOpen => {
+                let elements = self.arguments(TokenType::BracketClose)?;
+                Ok(Expr::Array(elements, token))
+            },
+            TokenType::ParenthesisOpen => {
+                self.next();
+                let inner = self.expression(OR_PRECEDENCE)?;
+                if self.current.token_type != TokenType::ParenthesisClose {
+                    return Err(self.error("unclosed Parenthesis".to_string(), &token))
+                }
+                self.next();
+                Ok(inner)
+            },
+            // the expression ended early, like `1 +`
+            TokenType::NullForParser => Err(self.error("Expected a value after this".to_string(), &token)),
+            _ => Err(self.error(format!("Expected a value got '{:?}' instead", token.token_type), &token))
+        }
+    }
+    fn negative_literal(&mut self, minus: &Token) -> Option<Expr> {
+        // `-170141183460469231731687303715884105728` is the smallest int, its digits alone are too large for one
+        if self.current.token_type != TokenType::Integer || self.current.value.parse::<i128>().is_ok() {
+            return None
+        }
+        let value = format!("-{}", self.current.value).parse::<i128>().ok()?;
+        let mut literal = self.current.clone();
+        literal.length += literal.x - minus.x;
+        literal.set_xy(minus.x, minus.y);
         self.next();
-
-        while self.terms.contains(&self.current.value) {
-            let operation = self.current.value.clone();
-            self.next();
-            let right = self.factor();
-            let result = self.next();
-            if operation == "*" {
-                left = Expr::Multiply(Box::new(right), Box::new(left))
-            } else {
-                left = Expr::Division(Box::new(right), Box::new(left))
+        Some(Expr::Integer(value, literal))
+    }
+    fn cast_type(&mut self) -> Result<VarTypes, Diagnostic> {
+        // only numbers and chars can be cast, `x as float`
+        let type_name = self.current.clone();
+        match str_to_types(type_name.value.clone()) {
+            Some(cast_type @ (VarTypes::Int | VarTypes::Float | VarTypes::Sized(_) | VarTypes::BigInt | VarTypes::Char)) if type_name.token_type == TokenType::Identifier => {
+                self.next();
+                Ok(cast_type)
+            },
+            _ => {
+                let message = if type_name.token_type == TokenType::Identifier {
+                    format!("values can't be cast to '{}'", type_name.value)
+                } else {
+                    format!("Expected a type after 'as' got '{:?}' instead", type_name.token_type)
+                };
+                Err(self.error(message, &type_name).with_note("values can be cast to int, float, i8, i16, i32, i64, u8, u16, u32, u64, bigint and char".to_string()))
             }
-            if !result {
-                break
+        }
+    }
+    fn prefix(&mut self) -> Result<Expr, Diagnostic> {
+        let token = self.current.clone();
+        if token.token_type == TokenType::MathOperation && token.value == "-" {
+            self.next();
+            if let Some(literal) = self.negative_literal(&token) {
+                return Ok(literal)
             }
-
+            let operand = self.expression(NEGATIVE_PRECEDENCE)?;
+            Ok(Expr::Negative(Box::new(operand), token))
+        } else if token.token_type == TokenType::MathOperation && token.value == "+" {
+            self.next();
+            let operand = self.expression(NEGATIVE_PRECEDENCE)?;
+            Ok(Expr::Positive(Box::new(operand), token))
+        } else if token.token_type == TokenType::Not {
+            self.next();
+            let operand = self.expression(NOT_PRECEDENCE)?;
+            Ok(Expr::Not(Box::new(operand), token))
+        } else {
+            self.factor()
         }
-        left
     }
-    fn expression(&mut self) -> Expr {
-        let mut left = self.term();
+    fn expression(&mut self, min_precedence: u8) -> Result<Expr, Diagnostic> {
+        let mut left = self.prefix()?;
 
-        while self.expression.contains(&self.current.value) {
-            let operation = self.current.value.clone();
+        while let Some(precedence) = binary_precedence(&self.current) {
+            if precedence < min_precedence {
+                break
+            }
+            let operation = self.current.clone();
             self.next();
-            let right = self.term();
-            let result = self.next();
-            if operation == "+" {
-                left = Expr::Addition(Box::new(right), Box::new(left))
-            } else {
-                left = Expr::Subtraction(Box::new(right), Box::new(left))
+            if operation.token_type == TokenType::As {
+                left = Expr::Cast(Box::new(left), self.cast_type()?, operation);
+                continue
             }
-            if !result {
-                break
+            // + 1 keeps operators of the same precedence out of the right side, which makes them left associative
+            let right_associative = operation.value == "**";
+            let right = Box::new(self.expression(if right_associative { precedence } else { precedence + 1 })?);
+            let left_side = Box::new(left);
+            left = match operation.token_type {
+                TokenType::Or => Expr::Or(left_side, right, operation),
+                TokenType::And => Expr::And(left_side, right, operation),
+                TokenType::ComparisonOperation => Expr::Comparison(left_side, right, operation),
+                _ => match &*operation.value {
+                    "+" => Expr::Addition(left_side, right, operation),
+                    "-" => Expr::Subtraction(left_side, right, operation),
+                    "*" => Expr::Multiply(left_side, right, operation),
+                    "/" => Expr::Division(left_side, right, operation),
+                    "**" => Expr::Power(left_side, right, operation),
+                    _ => Expr::Modulo(left_side, right, operation)
+                }
             }
-
         }
-        left
+        Ok(left)
     }
-    pub fn parse(&mut self, given: Vec<Token>) -> Expr {
+    pub fn parse(&mut self, given: Vec<Token>) -> Result<Expr, Diagnostic> {
+        if let Some(first) = given.first() {
+            self.current.set_xy(first.x, first.y);
+        }
         self.given = given;
         self.next();
-        self.expression()
-
+        let parsed = self.expression(OR_PRECEDENCE)?;
+        if self.current.token_type != TokenType::NullForParser {
+            return Err(self.error(format!("Unexpected '{:?}' in expression", self.current.token_type), &self.current))
+        }
+        Ok(parsed)
     }
 }
 
 #[derive(Debug, Clone)]
 pub enum Expr {
-    String(String),
-    Float(f64),
-    Integer(i128),
-    Bool(bool),
-
-    Addition(Box<Expr>, Box<Expr>),
-    Multiply(Box<Expr>, Box<Expr>),
-    Division(Box<Expr>, Box<Expr>),
-    Subtraction(Box<Expr>, Box<Expr>),
+    // literals keep their token for the position
+    String(String, Token),
+    Float(f64, Token),
+    Integer(i128, Token),
+    BigInteger(BigInt, Token),
+    Char(char, Token),
+    Bool(bool, Token),
+
+    Variable(Token),
+    Call(Token, Vec<Expr>),
+    // elements, opening bracket
+    Array(Vec<Expr>, Token),
+    // array, index, opening bracket
+    Index(Box<Expr>, Box<Expr>, Token),
+    // struct, field name
+    Field(Box<Expr>, Token),
+    // struct or struct name for functions without `self`, method name, arguments
+    MethodCall(Box<Expr>, Token, Vec<Expr>),
+    // the text and expressions of a string with expressions in it, the string
+    Interpolation(Vec<Expr>, Token),
+
+    // left, right, operator
+    Addition(Box<Expr>, Box<Expr>, Token),
+    Multiply(Box<Expr>, Box<Expr>, Token),
+    Division(Box<Expr>, Box<Expr>, Token),
+    Subtraction(Box<Expr>, Box<Expr>, Token),
+    Modulo(Box<Expr>, Box<Expr>, Token),
+    Power(Box<Expr>, Box<Expr>, Token),
+    Comparison(Box<Expr>, Box<Expr>, Token),
+    And(Box<Expr>, Box<Expr>, Token),
+    Or(Box<Expr>, Box<Expr>, Token),
+
+    // value, type, `as`
+    Cast(Box<Expr>, VarTypes, Token),
+
+    // operand, operator
+    Negative(Box<Expr>, Token),
+    Positive(Box<Expr>, Token),
+    Not(Box<Expr>, Token),
+}
+
+impl Expr {
+    pub fn token(&self) -> &Token {
+        // the token errors about this expression point at, operators for operations
+        match self {
+            Expr::String(_, token) | Expr::Float(_, token) | Expr::Integer(_, token) | Expr::BigInteger(_, token) |
+            Expr::Char(_, token) | Expr::Bool(_, token) => token,
+            Expr::Variable(token) | Expr::Call(token, _) | Expr::Interpolation(_, token) => token,
+            Expr::Array(_, token) | Expr::Index(.., token) | Expr::Field(_, token) | Expr::MethodCall(_, token, _) => token,
+            Expr::Addition(.., token) | Expr::Multiply(.., token) | Expr::Division(.., token) |
+            Expr::Subtraction(.., token) | Expr::Modulo(.., token) | Expr::Power(.., token) | Expr::Comparison(.., token) |
+            Expr::And(.., token) | Expr::Or(.., token) => token,
+            Expr::Negative(_, token) | Expr::Positive(_, token) | Expr::Not(_, token) | Expr::Cast(.., token) => token,
+        }
+    }
+    pub fn root(&self) -> &Expr {
+        // the variable an element is taken from, `a` for `a[0].x`
+        match self {
+            Expr::Index(array, ..) | Expr::Field(array, _) => array.root(),
+            expression => expression
+        }
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+
+    fn grouped(expression: &Expr) -> String {
+        // every operation in parentheses, so the tests see how the parser grouped them
+        let binary = |left: &Expr, operator: &str, right: &Expr| format!("({} {} {})", grouped(left), operator, grouped(right));
+        match expression {
+            Expr::Addition(left, right, _) => binary(left, "+", right),
+            Expr::Subtraction(left, right, _) => binary(left, "-", right),
+            Expr::Multiply(left, right, _) => binary(left, "*", right),
+            Expr::Division(left, right, _) => binary(left, "/", right),
+            Expr::Modulo(left, right, _) => binary(left, "%", right),
+            Expr::Power(left, right, _) => binary(left, "**", right),
+            Expr::Comparison(left, right, operator) => binary(left, &operator.value, right),
+            Expr::And(left, right, _) => binary(left, "and", right),
+            Expr::Or(left, right, _) => binary(left, "or", right),
+            Expr::Cast(value, var_type, _) => format!("({} as {:?})", grouped(value), var_type),
+            Expr::Negative(operand, _) => format!("(-{})", grouped(operand)),
+            Expr::Positive(operand, _) => format!("(+{})", grouped(operand)),
+            Expr::Not(operand, _) => format!("(not {})", grouped(operand)),
+            Expr::Integer(value, _) => value.to_string(),
+            Expr::Float(value, _) => value.to_string(),
+            Expr::Bool(value, _) => value.to_string(),
+            Expr::Variable(name) => name.value.clone(),
+            Expr::Call(name, arguments) => format!("{}({})", name.value, arguments.iter().map(grouped).collect::<Vec<_>>().join(", ")),
+            Expr::Index(array, index, _) => format!("{}[{}]", grouped(array), grouped(index)),
+            Expr::Field(object, field) => format!("{}.{}", grouped(object), field.value),
+            expression => panic!("the tests don't group {:?}", expression)
+        }
+    }
+
+    fn single_test(expression: &str, expected: &str) {
+        let mut tokens = Lexer::new().lex_text(expression.to_string()).unwrap_or_else(|error| panic!("{}", error));
+        tokens.retain(|token| token.token_type != TokenType::EndOfFile);
+        let parsed = Math::new().parse(tokens).unwrap_or_else(|error| panic!("{}", error));
+        assert_eq!(grouped(&parsed), expected, "{}", expression);
+    }
+
+    #[test]
+    fn precedence() {
+        single_test("1 + 2 * 3", "(1 + (2 * 3))");
+        single_test("1 * 2 + 3", "((1 * 2) + 3)");
+        single_test("1 + 2 % 3 - 4 / 5", "((1 + (2 % 3)) - (4 / 5))");
+        single_test("1 + 2 < 3 * 4", "((1 + 2) < (3 * 4))");
+        single_test("a < b and c or d == e", "(((a < b) and c) or (d == e))");
+        single_test("a or b and c", "(a or (b and c))");
+        single_test("not a == b and c", "((not (a == b)) and c)");
+        single_test("2 * 3 ** 2", "(2 * (3 ** 2))");
+        single_test("-a ** 2", "(-(a ** 2))");
+        single_test("-a * b", "((-a) * b)");
+        single_test("a as float * 2", "((a as Float) * 2)");
+        single_test("-a as float", "((-a) as Float)");
+        single_test("a[1] ** b.c", "(a[1] ** b.c)");
+        single_test("f(1 + 2) * 3", "(f((1 + 2)) * 3)");
+        single_test("(1 + 2) * 3", "((1 + 2) * 3)");
+    }
+
+    #[test]
+    fn associativity() {
+        single_test("10 - 3 - 2", "((10 - 3) - 2)");
+        single_test("8 / 4 / 2", "((8 / 4) / 2)");
+        single_test("1 + 2 - 3 + 4", "(((1 + 2) - 3) + 4)");
+        single_test("2 ** 3 ** 2", "(2 ** (3 ** 2))");
+        single_test("a or b or c", "((a or b) or c)");
+        single_test("a as int as float", "((a as Int) as Float)");
+        single_test("- - a", "(-(-a))");
+    }
+
+    #[test]
+    fn unfinished_expressions_are_errors() {
+        for expression in ["1 +", "* 2", "(1 + 2", "1 2"] {
+            let mut tokens = Lexer::new().lex_text(expression.to_string()).unwrap();
+            tokens.retain(|token| token.token_type != TokenType::EndOfFile);
+            assert!(Math::new().parse(tokens).is_err(), "{}", expression);
+        }
+    }
+}
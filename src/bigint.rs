@@ -0,0 +1,392 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+
+// ints of any size for `bigint`, the ones that fit in an i128 stay one so most arithmetic is as fast as `int`
+// the representation is private so every value is written only one way, see `from_parts`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigInt(Repr);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Repr {
+    Small(i128),
+    // whether it's negative and its digits in base 2^32, lowest first, only for values that don't fit in an i128
+    Large(bool, Vec<u32>),
+}
+
+// the largest power of ten that fits in a digit, numbers are read and written 9 decimal digits at a time
+const DECIMAL_CHUNK: u32 = 1_000_000_000;
+
+fn trim(mut digits: Vec<u32>) -> Vec<u32> {
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    digits
+}
+
+fn compare_magnitude(left: &[u32], right: &[u32]) -> Ordering {
+    // the digits are trimmed, more of them is a larger number
+    left.len().cmp(&right.len()).then_with(|| left.iter().rev().cmp(right.iter().rev()))
+}
+
+fn add_magnitude(left: &[u32], right: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(left.len().max(right.len()) + 1);
+    let mut carry = 0u64;
+    for index in 0..left.len().max(right.len()) {
+        let sum = *left.get(index).unwrap_or(&0) as u64 + *right.get(index).unwrap_or(&0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    result.push(carry as u32);
+    trim(result)
+}
+
+fn subtract_magnitude(left: &[u32], right: &[u32]) -> Vec<u32> {
+    // left is at least as large as right
+    let mut result = Vec::with_capacity(left.len());
+    let mut borrow = 0i64;
+    for (index, digit) in left.iter().enumerate() {
+        let mut difference = *digit as i64 - *right.get(index).unwrap_or(&0) as i64 - borrow;
+        borrow = (difference < 0) as i64;
+        if difference < 0 {
+            difference += 1 << 32;
+        }
+        result.push(difference as u32);
+    }
+    trim(result)
+}
+
+fn multiply_magnitude(left: &[u32], right: &[u32]) -> Vec<u32> {
+    let mut result = vec![0u32; left.len() + right.len()];
+    for (left_index, left_digit) in left.iter().enumerate() {
+        let mut carry = 0u64;
+        for (right_index, right_digit) in right.iter().enumerate() {
+            let slot = &mut result[left_index + right_index];
+            let product = *left_digit as u64 * *right_digit as u64 + *slot as u64 + carry;
+            *slot = product as u32;
+            carry = product >> 32;
+        }
+        result[left_index + right.len()] = carry as u32;
+    }
+    trim(result)
+}
+
+fn divide_small(digits: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0u32; digits.len()];
+    let mut remainder = 0u64;
+    for (index, digit) in digits.iter().enumerate().rev() {
+        let current = (remainder << 32) | *digit as u64;
+        quotient[index] = (current / divisor as u64) as u32;
+        remainder = current % divisor as u64;
+    }
+    (trim(quotient), remainder as u32)
+}
+
+fn shift_left(digits: &[u32], bits: usize) -> Vec<u32> {
+    let mut result = vec![0u32; bits / 32];
+    let shift = bits % 32;
+    let mut carry = 0u32;
+    for digit in digits {
+        if shift == 0 {
+            result.push(*digit);
+        } else {
+            result.push((digit << shift) | carry);
+            carry = digit >> (32 - shift);
+        }
+    }
+    result.push(carry);
+    trim(result)
+}
+
+fn divide_magnitude(left: &[u32], right: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    // long division one bit at a time, dividing by a single digit is the usual case and done a digit at a time
+    if let [divisor] = right {
+        let (quotient, remainder) = divide_small(left, *divisor);
+        return (quotient, trim(vec![remainder]))
+    }
+    let mut quotient = vec![0u32; left.len()];
+    let mut remainder: Vec<u32> = vec![];
+    for bit in (0..left.len() * 32).rev() {
+        // moves the remainder one bit up and brings down the next bit of left
+        let mut carry = left[bit / 32] >> (bit % 32) & 1;
+        for digit in remainder.iter_mut() {
+            let next = *digit >> 31;
+            *digit = (*digit << 1) | carry;
+            carry = next;
+        }
+        if carry != 0 {
+            remainder.push(carry);
+        }
+        if compare_magnitude(&remainder, right) != Ordering::Less {
+            remainder = subtract_magnitude(&remainder, right);
+            quotient[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+    (trim(quotient), remainder)
+}
+
+impl BigInt {
+    fn from_parts(negative: bool, digits: Vec<u32>) -> Self {
+        // goes back to an i128 when the value fits in one, so every value is written only one way
+        let digits = trim(digits);
+        if digits.len() <= 4 {
+            let magnitude = digits.iter().rev().fold(0u128, |value, digit| (value << 32) | *digit as u128);
+            if !negative && magnitude <= i128::MAX as u128 {
+                return BigInt(Repr::Small(magnitude as i128))
+            }
+            if negative && magnitude <= i128::MIN.unsigned_abs() {
+                return BigInt(Repr::Small((magnitude as i128).wrapping_neg()))
+            }
+        }
+        BigInt(Repr::Large(negative, digits))
+    }
+    fn parts(&self) -> (bool, Vec<u32>) {
+        match &self.0 {
+            Repr::Small(value) => {
+                let magnitude = value.unsigned_abs();
+                (*value < 0, trim((0..4).map(|index| (magnitude >> (index * 32)) as u32).collect()))
+            },
+            Repr::Large(negative, digits) => (*negative, digits.clone())
+        }
+    }
+    // decimal digits with an optional `-`, None if there is anything else
+    pub fn parse(text: &str) -> Option<Self> {
+        if let Ok(value) = text.parse::<i128>() {
+            return Some(BigInt(Repr::Small(value)))
+        }
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, text)
+        };
+        if digits.is_empty() || !digits.bytes().all(|digit| digit.is_ascii_digit()) {
+            return None
+        }
+        let mut magnitude = vec![];
+        // the first chunk takes the digits left over so the rest have 9 each
+        let first = match digits.len() % 9 {
+            0 => 9,
+            length => length
+        };
+        let mut start = 0;
+        let mut end = first;
+        while start < digits.len() {
+            let chunk: u32 = digits[start..end].parse().ok()?;
+            magnitude = add_magnitude(&multiply_magnitude(&magnitude, &[DECIMAL_CHUNK]), &[chunk]);
+            start = end;
+            end += 9;
+        }
+        Some(Self::from_parts(negative, magnitude))
+    }
+    // None when it doesn't fit in an i128
+    pub fn to_i128(&self) -> Option<i128> {
+        match &self.0 {
+            Repr::Small(value) => Some(*value),
+            Repr::Large(..) => None
+        }
+    }
+    // how many bits the value takes without its sign, 0 for 0
+    pub fn bit_length(&self) -> u64 {
+        match &self.0 {
+            Repr::Small(value) => 128 - value.unsigned_abs().leading_zeros() as u64,
+            Repr::Large(_, digits) => digits.len() as u64 * 32 - digits.last().map_or(32, |digit| digit.leading_zeros()) as u64
+        }
+    }
+    pub fn is_zero(&self) -> bool {
+        self.0 == Repr::Small(0)
+    }
+    pub fn pow(&self, exponent: u32) -> Self {
+        // a power of two only moves its one bit, anything else squares the base for every bit of the exponent
+        let (negative, digits) = self.parts();
+        if digits.iter().map(|digit| digit.count_ones()).sum::<u32>() == 1 {
+            let bits = (self.bit_length() - 1) as usize * exponent as usize;
+            return Self::from_parts(negative && exponent % 2 == 1, shift_left(&[1], bits))
+        }
+        let mut result = BigInt(Repr::Small(1));
+        let mut base = self.clone();
+        let mut exponent = exponent;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+    // the lowest 128 bits as a two's complement int, like casting to a smaller int does
+    pub fn low_bits(&self) -> i128 {
+        match &self.0 {
+            Repr::Small(value) => *value,
+            Repr::Large(negative, digits) => {
+                let magnitude = digits.iter().take(4).rev().fold(0u128, |value, digit| (value << 32) | *digit as u128) as i128;
+                if *negative { magnitude.wrapping_neg() } else { magnitude }
+            }
+        }
+    }
+    pub fn to_f64(&self) -> f64 {
+        match &self.0 {
+            Repr::Small(value) => *value as f64,
+            Repr::Large(negative, digits) => {
+                let magnitude = digits.iter().rev().fold(0.0, |value, digit| value * 4294967296.0 + *digit as f64);
+                if *negative { -magnitude } else { magnitude }
+            }
+        }
+    }
+    // cut toward zero, None for inf and NaN
+    pub fn from_f64(value: f64) -> Option<Self> {
+        if !value.is_finite() {
+            return None
+        }
+        let value = value.trunc();
+        if value.abs() < 2f64.powi(127) {
+            return Some(BigInt(Repr::Small(value as i128)))
+        }
+        // floats this large have no fraction, they are their 53 bit mantissa moved left
+        let bits = value.to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as usize - 1075;
+        let mantissa = (bits & ((1 << 52) - 1)) | (1 << 52);
+        let digits = shift_left(&[mantissa as u32, (mantissa >> 32) as u32], exponent);
+        Some(Self::from_parts(value < 0.0, digits))
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(value: i128) -> Self {
+        BigInt(Repr::Small(value))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if let (Repr::Small(left), Repr::Small(right)) = (&self.0, &other.0) {
+            return left.cmp(right)
+        }
+        match (self.parts(), other.parts()) {
+            ((true, _), (false, _)) => Ordering::Less,
+            ((false, _), (true, _)) => Ordering::Greater,
+            ((false, left), (false, right)) => compare_magnitude(&left, &right),
+            ((true, left), (true, right)) => compare_magnitude(&right, &left),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+    fn add(self, other: &BigInt) -> BigInt {
+        if let (Repr::Small(left), Repr::Small(right)) = (&self.0, &other.0) {
+            if let Some(sum) = left.checked_add(*right) {
+                return BigInt(Repr::Small(sum))
+            }
+        }
+        let ((left_negative, left), (right_negative, right)) = (self.parts(), other.parts());
+        if left_negative == right_negative {
+            return BigInt::from_parts(left_negative, add_magnitude(&left, &right))
+        }
+        // the sign of the side further from zero wins
+        match compare_magnitude(&left, &right) {
+            Ordering::Less => BigInt::from_parts(right_negative, subtract_magnitude(&right, &left)),
+            _ => BigInt::from_parts(left_negative, subtract_magnitude(&left, &right))
+        }
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+    fn neg(self) -> BigInt {
+        if let Repr::Small(value) = &self.0 {
+            if let Some(negated) = value.checked_neg() {
+                return BigInt(Repr::Small(negated))
+            }
+        }
+        let (negative, digits) = self.parts();
+        BigInt::from_parts(!negative, digits)
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+    fn sub(self, other: &BigInt) -> BigInt {
+        if let (Repr::Small(left), Repr::Small(right)) = (&self.0, &other.0) {
+            if let Some(difference) = left.checked_sub(*right) {
+                return BigInt(Repr::Small(difference))
+            }
+        }
+        self + &-other
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+    fn mul(self, other: &BigInt) -> BigInt {
+        if let (Repr::Small(left), Repr::Small(right)) = (&self.0, &other.0) {
+            if let Some(product) = left.checked_mul(*right) {
+                return BigInt(Repr::Small(product))
+            }
+        }
+        let ((left_negative, left), (right_negative, right)) = (self.parts(), other.parts());
+        BigInt::from_parts(left_negative != right_negative, multiply_magnitude(&left, &right))
+    }
+}
+
+// division is cut toward zero and the remainder has the sign of the left side, like for `int`
+// dividing by zero panics, the interpreter checks for it first
+impl Div for &BigInt {
+    type Output = BigInt;
+    fn div(self, other: &BigInt) -> BigInt {
+        if let (Repr::Small(left), Repr::Small(right)) = (&self.0, &other.0) {
+            if let Some(quotient) = left.checked_div(*right) {
+                return BigInt(Repr::Small(quotient))
+            }
+        }
+        let ((left_negative, left), (right_negative, right)) = (self.parts(), other.parts());
+        assert!(!right.is_empty(), "bigint division by zero");
+        BigInt::from_parts(left_negative != right_negative, divide_magnitude(&left, &right).0)
+    }
+}
+
+impl Rem for &BigInt {
+    type Output = BigInt;
+    fn rem(self, other: &BigInt) -> BigInt {
+        if let (Repr::Small(left), Repr::Small(right)) = (&self.0, &other.0) {
+            if let Some(remainder) = left.checked_rem(*right) {
+                return BigInt(Repr::Small(remainder))
+            }
+        }
+        let ((left_negative, left), (_, right)) = (self.parts(), other.parts());
+        assert!(!right.is_empty(), "bigint division by zero");
+        BigInt::from_parts(left_negative, divide_magnitude(&left, &right).1)
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (negative, mut digits) = match &self.0 {
+            Repr::Small(value) => return write!(f, "{}", value),
+            Repr::Large(negative, digits) => (*negative, digits.clone())
+        };
+        // split into chunks of 9 decimal digits, lowest first
+        let mut chunks = vec![];
+        while !digits.is_empty() {
+            let (quotient, remainder) = divide_small(&digits, DECIMAL_CHUNK);
+            chunks.push(remainder);
+            digits = quotient;
+        }
+        if negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", chunks.pop().unwrap_or(0))?;
+        for chunk in chunks.iter().rev() {
+            write!(f, "{:09}", chunk)?;
+        }
+        Ok(())
+    }
+}
@@ -1,78 +1,118 @@
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use crate::ast::Expr;
 use crate::lexer::{Token, TokenType};
-use crate::Parsed;
-use crate::parser::{data_token_type_to_types, str_to_types, VarTypes};
+use crate::parser::Parsed;
+use crate::parser::{IntType, VarTypes};
+use crate::error::{Diagnostic, ErrorKind, Span};
+use crate::native::{NativeFunction, Natives};
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-enum ParseTypes {
-    Int,
-    Float,
-    Bool,
-    String,
-    Math,
-    Comparison,
-    ParenthesisOpen,
-    ParenthesisClose
+fn type_error(message: String, token: &Token) -> Diagnostic {
+    Diagnostic::at_token(ErrorKind::Type, message, token)
 }
 
-fn token_type_to_parse_type(token: & Token) -> Option<ParseTypes> {
-    // Converts TokenType -> ParseType
-    match token.token_type {
-        TokenType::Integer => Some(ParseTypes::Int),
-        TokenType::String => Some(ParseTypes::String),
-        TokenType::FloatingPoint => Some(ParseTypes::Float),
-        TokenType::Boolean => Some(ParseTypes::Bool),
-        TokenType::ParenthesisOpen => Some(ParseTypes::ParenthesisOpen),
-        TokenType::ParenthesisClose => Some(ParseTypes::ParenthesisClose),
-        TokenType::MathOperation => Some(ParseTypes::Math),
-        TokenType::ComparisonOperation => Some(ParseTypes::Comparison),
-        _ => None
+fn name_error(message: String, token: &Token) -> Diagnostic {
+    Diagnostic::at_token(ErrorKind::Name, message, token)
+}
+
+fn redeclared(what: &str, name: &Token, first: Option<&Token>) -> Diagnostic {
+    let error = name_error(format!("{} '{}' already exists", what, name.value), name)
+        .with_label("declared again here".to_string());
+    match first {
+        Some(first) => error.with_secondary(Span::from_token(first), "first declared here".to_string()),
+        None => error
     }
 }
 
-fn var_types_to_parse_type(token: &VarTypes) -> Option<ParseTypes> {
-    // Converts VarTypes -> ParseType
-    match token {
-        VarTypes::Bool => Some(ParseTypes::Bool),
-        VarTypes::Int => Some(ParseTypes::Int),
-        VarTypes::Float => Some(ParseTypes::Float),
-        VarTypes::Str => Some(ParseTypes::String),
-        _ => None
+fn compatible(found: &VarTypes, expected: &VarTypes) -> bool {
+    // types with an error somewhere in them were already reported
+    match (found, expected) {
+        (VarTypes::Error, _) | (_, VarTypes::Error) => true,
+        (VarTypes::Array(found), VarTypes::Array(expected)) => compatible(found, expected),
+        (found, expected) => found == expected
+    }
+}
+
+fn accepts(expected: &ArgTypes, given: &VarTypes) -> bool {
+    // whether an argument of the given type fits a parameter
+    match (expected, given) {
+        (_, VarTypes::Error) | (ArgTypes::Any, _) => true,
+        (ArgTypes::OneOf(options), given) => options.iter().any(|option| accepts(option, given)),
+        (ArgTypes::Array(expected), VarTypes::Array(given)) => accepts(expected, given),
+        (expected, given) => var_types_to_arg_type(given).as_ref() == Some(expected)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Method {
+    // methods take `self` first, functions like `Point.new()` don't
+    takes_self: bool,
+    // without `self`
+    arguments: Vec<ArgTypes>,
+    return_type: Vec<VarTypes>,
+    declared: Token
+}
+
+#[derive(Debug, Clone)]
+struct StructType {
+    // in the order they are given when constructing
+    fields: Vec<(String, VarTypes)>,
+    // from the impl blocks of the struct
+    methods: HashMap<String, Method>,
+}
+
+impl StructType {
+    fn field(&self, name: &str) -> Option<&VarTypes> {
+        self.fields.iter().find(|field| field.0 == name).map(|field| &field.1)
     }
 }
 
-fn parse_type_to_var_types(token: &ParseTypes) -> Option<VarTypes> {
-    // Converts ParseType -> VarTypes
-    match token {
-       ParseTypes::String => Some(VarTypes::Str),
-       ParseTypes::Bool => Some(VarTypes::Bool),
-       ParseTypes::Int => Some(VarTypes::Int),
-       ParseTypes::Float => Some(VarTypes::Float),
+fn is_number(var_type: &VarTypes) -> bool {
+    // errors count as numbers so they aren't reported again
+    matches!(var_type, VarTypes::Int | VarTypes::Sized(_) | VarTypes::BigInt | VarTypes::Float | VarTypes::Error)
+}
+
+fn int_literal(expression: &Expr) -> Option<i128> {
+    // `5` or `-5`, they take the sized int or bigint type they are used as
+    match expression {
+        Expr::Integer(value, _) => Some(*value),
+        Expr::Negative(operand, _) => int_literal(operand)?.checked_neg(),
         _ => None
     }
 }
 
+pub(crate) fn arg_type_to_var_type(arg: &ArgTypes) -> Option<VarTypes> {
+    match arg {
+        ArgTypes::Int => Some(VarTypes::Int),
+        ArgTypes::Sized(int_type) => Some(VarTypes::Sized(*int_type)),
+        ArgTypes::BigInt => Some(VarTypes::BigInt),
+        ArgTypes::Str => Some(VarTypes::Str),
+        ArgTypes::Char => Some(VarTypes::Char),
+        ArgTypes::Bool => Some(VarTypes::Bool),
+        ArgTypes::Float => Some(VarTypes::Float),
+        ArgTypes::Array(element_type) => Some(VarTypes::Array(Box::new(arg_type_to_var_type(element_type)?))),
+        ArgTypes::Struct(name) => Some(VarTypes::Struct(name.clone())),
+        ArgTypes::Any | ArgTypes::OneOf(_) | ArgTypes::Variadic(_) => None
+    }
+}
 
-struct TypeEvaluator {
-    to_parse: Vec<Token>,
-    defined_var: HashMap<String, VarTypes>,
-    defined_struct: HashMap<String, VarTypes>,
+struct TypeEvaluator<'a> {
+    defined_var: &'a HashMap<String, VarTypes>,
+    defined_struct: &'a HashMap<String, StructType>,
     //                                 args type      return type
-    defined_function: HashMap<String, (Vec<ArgTypes>, Vec<VarTypes>)>,
-    removed: Vec<String>
+    defined_function: &'a HashMap<String, (Vec<ArgTypes>, Vec<VarTypes>)>,
+    removed: &'a Vec<String>
 }
 
-impl TypeEvaluator {
-    fn new(to_evaluate: Vec<Token>,
-           defined_var: HashMap<String, VarTypes>,
-           defined_struct: HashMap<String, VarTypes>,
-           defined_function: HashMap<String, (Vec<ArgTypes>, Vec<VarTypes>)>,
-           removed: Vec<String>) -> Self
+impl<'a> TypeEvaluator<'a> {
+    fn new(defined_var: &'a HashMap<String, VarTypes>,
+           defined_struct: &'a HashMap<String, StructType>,
+           defined_function: &'a HashMap<String, (Vec<ArgTypes>, Vec<VarTypes>)>,
+           removed: &'a Vec<String>) -> Self
     {
 
         Self {
-            to_parse: to_evaluate,
             defined_var,
             defined_struct,
             defined_function,
@@ -80,141 +120,388 @@ impl TypeEvaluator {
         }
     }
 
-    fn eval(&mut self, evaluate: Vec<(ParseTypes, u32, u32)>) -> (ParseTypes, u32, u32) {
-        let mut curly_brackets_count = 0; // represents {}
-        let mut values_to_eval = vec![]; // to eval, to recurse return gets added here
-        let mut values_to_recurse = vec![]; // to recurse
-        for part in evaluate {
-            if part.0 == ParseTypes::ParenthesisClose {
-                curly_brackets_count -= 1;
-                if curly_brackets_count != 0 {
-                    values_to_recurse.push(part);
-                } else if curly_brackets_count == 0 {
-                    // runs a recursion of self.eval
-                    values_to_eval.push(self.eval(values_to_recurse.clone()));
-                    values_to_recurse.clear();
+    fn same_types(&self, left: &Expr, right: &Expr, operator: &Token) -> Result<VarTypes, Diagnostic> {
+        // both sides of an operation have to be of the same type, an int literal takes the type of the other side
+        let left_type = self.eval(left)?;
+        let right_type = self.eval_as(right, &left_type)?;
+        let left_type = match (&left_type, &right_type) {
+            (VarTypes::Int, VarTypes::Sized(_) | VarTypes::BigInt) => self.eval_as(left, &right_type)?,
+            _ => left_type
+        };
+        if left_type == VarTypes::Error || right_type == VarTypes::Error {
+            return Ok(VarTypes::Error)
+        }
+        if !compatible(&right_type, &left_type) {
+            return Err(type_error(format!("Expected {:?} got '{:?}' instead", left_type, right_type), operator)
+                .with_note("both sides of an operation have to be the same type".to_string()))
+        }
+        Ok(left_type)
+    }
+
+    fn eval_as(&self, expression: &Expr, expected: &VarTypes) -> Result<VarTypes, Diagnostic> {
+        // like eval but int literals given where a sized int is expected are checked to fit in it, `u8 a <- 255;`
+        match (expected, expression) {
+            (VarTypes::Sized(int_type), expression) if int_literal(expression).is_some() => {
+                let value = int_literal(expression).unwrap();
+                if !int_type.contains(value) {
+                    return Err(type_error(format!("{} doesn't fit in '{:?}'", value, int_type), expression.token())
+                        .with_note(format!("'{:?}' goes from {} to {}", int_type, int_type.min(), int_type.max())))
                 }
-            } else if part.0 == ParseTypes::ParenthesisOpen {
-                if curly_brackets_count != 0 {
-                    // will be sent to recurse
-                    values_to_recurse.push(part)
+                Ok(expected.clone())
+            },
+            (VarTypes::BigInt, expression) if int_literal(expression).is_some() => Ok(VarTypes::BigInt),
+            // `[]` takes the type it is given to
+            (VarTypes::Array(_), Expr::Array(elements, _)) if elements.is_empty() => Ok(expected.clone()),
+            (VarTypes::Array(element_type), Expr::Array(elements, _)) if matches!(**element_type, VarTypes::Sized(_) | VarTypes::BigInt | VarTypes::Array(_)) => {
+                for element in elements {
+                    let found = self.eval_as(element, element_type)?;
+                    if !compatible(&found, element_type) {
+                        return self.eval(expression)
+                    }
                 }
-                curly_brackets_count += 1;
-            } else if curly_brackets_count != 0 {
-                // will be sent to recurse
-                values_to_recurse.push(part);
-            } else {
-                // stuff that isn't inside {}
-                values_to_eval.push(part);
-            }
+                Ok(expected.clone())
+            },
+            _ => self.eval(expression)
         }
-        let mut current_index = -1;
-        let mut round_finished = 0;
-        while values_to_eval.len() != 1 {
-            current_index += 1;
-            if round_finished == 1 {
-                // if after going another round and nothing changed
-                break
-            }
-            if current_index >= values_to_eval.len() as i32{
-                current_index = 0;
-                round_finished += 1;
-            }
-            let current = values_to_eval.get(current_index as usize).unwrap();
-            if current.0 == ParseTypes::Math {
-                round_finished = 0;
-                if values_to_eval.get((current_index - 1) as usize).unwrap().0 == values_to_eval.get((current_index + 1) as usize).unwrap().0{
-                    values_to_eval.remove((current_index - 1) as usize);
-                    values_to_eval.remove((current_index - 1) as usize);
+    }
+
+    fn eval(&self, expression: &Expr) -> Result<VarTypes, Diagnostic> {
+        match expression {
+            Expr::String(..) => Ok(VarTypes::Str),
+            Expr::Float(..) => Ok(VarTypes::Float),
+            Expr::Integer(..) => Ok(VarTypes::Int),
+            Expr::BigInteger(..) => Ok(VarTypes::BigInt),
+            Expr::Char(..) => Ok(VarTypes::Char),
+            Expr::Bool(..) => Ok(VarTypes::Bool),
+            Expr::Variable(token) => {
+                if let Some(var_type) = self.defined_var.get(&token.value) {
+                    Ok(var_type.clone())
+                } else if self.removed.contains(&token.value) {
+                    Err(name_error(format!("The Variable '{}' is out of scope or undefined", token.value), token))
                 } else {
-                    // when before and after arent of same types
-                    let number_before = values_to_eval.get((current_index - 1) as usize).unwrap();
-                    let number_after = values_to_eval.get((current_index + 1) as usize).unwrap();
-                    panic!("Expected {:?} got '{:?}' instead, at line {} char {}", number_before.0, number_after.0, number_after.2, number_after.1)
+                    Err(name_error(format!("variable '{}' does not exists", token.value), token))
                 }
-            }
+            },
+            Expr::Call(func_name, args) => {
+                let return_type = self.call_check(func_name, args)?;
+                if return_type.is_empty() {
+                    return Err(type_error(format!("function '{}' does not return a value", func_name.value), func_name))
+                }
+                Ok(return_type[0].clone())
+            },
+            Expr::Array(elements, bracket) => {
+                let Some(first) = elements.first() else {
+                    return Err(type_error("the type of an empty array can't be told".to_string(), bracket)
+                        .with_note("empty arrays can only be given where an array type is expected, like `[int] a <- [];`".to_string()))
+                };
+                // every element has the type of the first one
+                let element_type = self.eval(first)?;
+                for element in &elements[1..] {
+                    let found = self.eval(element)?;
+                    if !compatible(&found, &element_type) {
+                        return Err(type_error(format!("Expected {:?} got '{:?}' instead", element_type, found), element.token())
+                            .with_note("all elements of an array have to be the same type".to_string()))
+                    }
+                }
+                Ok(VarTypes::Array(Box::new(element_type)))
+            },
+            Expr::Index(array, index, bracket) => {
+                // strings are indexed by character
+                let element_type = match self.eval(array)? {
+                    VarTypes::Array(element_type) => *element_type,
+                    VarTypes::Str => VarTypes::Char,
+                    VarTypes::Error => VarTypes::Error,
+                    found => return Err(type_error(format!("'{:?}' can't be indexed", found), bracket))
+                };
+                let index_type = self.eval(index)?;
+                if !compatible(&index_type, &VarTypes::Int) {
+                    return Err(type_error(format!("'{:?}' is indexed with 'Int' not '{:?}'", self.eval(array)?, index_type), index.token()))
+                }
+                Ok(element_type)
+            },
+            Expr::Field(object, field) => {
+                match self.eval(object)? {
+                    VarTypes::Struct(struct_name) => {
+                        // an unknown struct type was reported where it was written
+                        let Some(struct_type) = self.defined_struct.get(&struct_name) else {
+                            return Ok(VarTypes::Error)
+                        };
+                        match struct_type.field(&field.value) {
+                            Some(field_type) => Ok(field_type.clone()),
+                            None => Err(name_error(format!("struct '{}' has no field '{}'", struct_name, field.value), field))
+                        }
+                    },
+                    VarTypes::Error => Ok(VarTypes::Error),
+                    found => Err(type_error(format!("'{:?}' has no fields", found), field))
+                }
+            },
+            Expr::Interpolation(parts, _) => {
+                for part in parts {
+                    let part_type = self.eval(part)?;
+                    if !is_number(&part_type) && !matches!(part_type, VarTypes::Bool | VarTypes::Str | VarTypes::Char) {
+                        return Err(type_error(format!("'{:?}' can't be put in a string", part_type), part.token())
+                            .with_note("only numbers, 'Bool', 'Str' and 'Char' values can be".to_string()))
+                    }
+                }
+                Ok(VarTypes::Str)
+            },
+            Expr::MethodCall(receiver, method, args) => {
+                let return_type = self.method_check(receiver, method, args)?;
+                if return_type.is_empty() {
+                    return Err(type_error(format!("method '{}' does not return a value", method.value), method))
+                }
+                Ok(return_type[0].clone())
+            },
+            Expr::Addition(left, right, operator) => {
+                let result = self.same_types(left, right, operator)?;
+                if matches!(result, VarTypes::Bool | VarTypes::Char | VarTypes::Array(_) | VarTypes::Struct(_)) {
+                    return Err(type_error(format!("'+' can't be used on '{:?}'", result), operator))
+                }
+                Ok(result)
+            },
+            Expr::Subtraction(left, right, operator) | Expr::Multiply(left, right, operator) |
+            Expr::Division(left, right, operator) | Expr::Modulo(left, right, operator) => {
+                let result = self.same_types(left, right, operator)?;
+                if !is_number(&result) {
+                    return Err(type_error(format!("'{}' can't be used on '{:?}'", operator.value, result), operator))
+                }
+                Ok(result)
+            },
+            Expr::Power(left, right, operator) => {
+                // an int raised to an int stays one, a float on either side makes it a float
+                // the exponent of a sized int can be any int, only a bigint can be raised to a bigint
+                let (base, exponent) = (self.eval(left)?, self.eval(right)?);
+                for side in [&base, &exponent] {
+                    if !is_number(side) {
+                        return Err(type_error(format!("'**' can't be used on '{:?}'", side), operator))
+                    }
+                }
+                if exponent == VarTypes::BigInt && matches!(base, VarTypes::Int | VarTypes::Sized(_)) {
+                    return Err(type_error(format!("'{:?}' can't be raised to a 'BigInt'", base), operator)
+                        .with_note("the base can be cast with `as bigint`".to_string()))
+                }
+                Ok(match (base, exponent) {
+                    (VarTypes::Error, _) | (_, VarTypes::Error) => VarTypes::Error,
+                    (VarTypes::Float, _) | (_, VarTypes::Float) => VarTypes::Float,
+                    (base, _) => base
+                })
+            },
+            Expr::Comparison(left, right, operator) => {
+                // a sized int can be compared with any int literal, `a < 300` is always true for a u8
+                let other_side = match (int_literal(left), int_literal(right)) {
+                    (None, Some(_)) => Some(self.eval(left)?),
+                    (Some(_), None) => Some(self.eval(right)?),
+                    _ => None
+                };
+                let compared = match other_side {
+                    Some(sized @ VarTypes::Sized(_)) => sized,
+                    _ => self.same_types(left, right, operator)?
+                };
+                let ordering = operator.value != "==" && operator.value != "!=";
+                // chars are ordered by their code point
+                if ordering && !is_number(&compared) && compared != VarTypes::Char {
+                    return Err(type_error(format!("'{}' can't be used on '{:?}'", operator.value, compared), operator))
+                }
+                Ok(VarTypes::Bool)
+            },
+            Expr::And(left, right, operator) | Expr::Or(left, right, operator) => {
+                for side in [left, right] {
+                    let side_type = self.eval(side)?;
+                    if side_type != VarTypes::Bool && side_type != VarTypes::Error {
+                        let keyword = if operator.token_type == TokenType::And { "and" } else { "or" };
+                        return Err(type_error(format!("'{}' can't be used on '{:?}'", keyword, side_type), operator))
+                    }
+                }
+                Ok(VarTypes::Bool)
+            },
+            Expr::Cast(value, cast_type, operator) => {
+                // chars become their code point and back, only ints can do that
+                let value_type = self.eval(value)?;
+                let is_int = |var_type: &VarTypes| matches!(var_type, VarTypes::Int | VarTypes::Sized(_) | VarTypes::BigInt);
+                match (&value_type, cast_type) {
+                    (VarTypes::Error, _) | (VarTypes::Char, VarTypes::Char) => {},
+                    (VarTypes::Char, other) | (other, VarTypes::Char) if !is_int(other) => {
+                        return Err(type_error(format!("'{:?}' can't be cast to '{:?}'", value_type, cast_type), operator)
+                            .with_note("only ints can be cast to and from 'Char'".to_string()))
+                    },
+                    (VarTypes::Char, _) => {},
+                    (value_type, _) if !is_number(value_type) => {
+                        return Err(type_error(format!("'{:?}' can't be cast to '{:?}'", value_type, cast_type), operator)
+                            .with_note("only numbers and chars can be cast".to_string()))
+                    },
+                    _ => {}
+                }
+                Ok(cast_type.clone())
+            },
+            Expr::Negative(operand, operator) | Expr::Positive(operand, operator) => {
+                let operand_type = self.eval(operand)?;
+                if !is_number(&operand_type) {
+                    return Err(type_error(format!("'{}' can't be used on '{:?}'", operator.value, operand_type), operator))
+                }
+                Ok(operand_type)
+            },
+            Expr::Not(operand, operator) => {
+                let operand_type = self.eval(operand)?;
+                if operand_type != VarTypes::Bool && operand_type != VarTypes::Error {
+                    return Err(type_error(format!("'not' can't be used on '{:?}'", operand_type), operator))
+                }
+                Ok(VarTypes::Bool)
+            },
         }
+    }
 
-        let mut current_index2 = -1;
-        let mut round_finished2 = 0;
-        while values_to_eval.len() != 1 {
-            current_index2 += 1;
-            if round_finished2 == 1 {
-                break
-                // if after going another round and nothing changed
-            }
-            if current_index2 >= values_to_eval.len() as i32{
-                current_index2 = 0;
-                round_finished2 += 1;
-            }
-            let current = values_to_eval.get(current_index2 as usize).unwrap();
-            if current.0 == ParseTypes::Comparison {
-                if values_to_eval.get((current_index2 - 1) as usize).unwrap().0 == values_to_eval.get((current_index2 + 1) as usize).unwrap().0{
-                    values_to_eval.remove((current_index2 - 1) as usize);
-                    values_to_eval.remove((current_index2 - 1) as usize);
-                    let d = values_to_eval.remove((current_index2 - 1) as usize);
-                    values_to_eval.insert((current_index2 - 1) as usize, (ParseTypes::Bool, d.1, d.2));
-                } else {
-                    // when before and after arent of same types
-                    let number_before = values_to_eval.get((current_index2 - 1) as usize).unwrap();
-                    let number_after = values_to_eval.get((current_index2 + 1) as usize).unwrap();
-                    panic!("Expected {:?} got '{:?}' instead, at line {} char {}", number_before.0, number_after.0, number_after.2, number_after.1)
-                }
-            }
+    fn call_check(&self, func_name: &Token, func_args: &[Expr]) -> Result<Vec<VarTypes>, Diagnostic> {
+        // checks the arguments of a call against the function signature, returns the return type
+        if let Some(function_referred_to) = self.defined_function.get(&func_name.value) {
+            self.arguments_check(func_name, &function_referred_to.0, func_args)?;
+            return Ok(function_referred_to.1.clone())
         }
+        // structs are built by calling them with a value for every field, `Point(1, 2)`
+        if let Some(struct_type) = self.defined_struct.get(&func_name.value) {
+            let field_types: Vec<ArgTypes> = struct_type.fields.iter()
+                .map(|field| var_types_to_arg_type(&field.1).expect("struct fields have argument types"))
+                .collect();
+            self.arguments_check(func_name, &field_types, func_args)?;
+            return Ok(vec![VarTypes::Struct(func_name.value.clone())])
+        }
+        Err(name_error(format!("function '{}' does not exists", func_name.value), func_name))
+    }
 
-        if values_to_eval.len() > 1 {
-            panic!("Eval error")
+    fn method_check(&self, receiver: &Expr, method: &Token, func_args: &[Expr]) -> Result<Vec<VarTypes>, Diagnostic> {
+        // `Point.new()` calls a function of the struct, unless a variable is named like the struct
+        let on_struct = matches!(receiver,
+            Expr::Variable(name) if !self.defined_var.contains_key(&name.value) && self.defined_struct.contains_key(&name.value));
+        let struct_name = if on_struct {
+            receiver.token().value.clone()
+        } else {
+            match self.eval(receiver)? {
+                VarTypes::Struct(struct_name) => struct_name,
+                VarTypes::Error => return Ok(vec![VarTypes::Error]),
+                found => return Err(type_error(format!("'{:?}' has no methods", found), method))
+            }
+        };
+        let Some(struct_type) = self.defined_struct.get(&struct_name) else {
+            return Ok(vec![VarTypes::Error])
+        };
+        let Some(found) = struct_type.methods.get(&method.value) else {
+            return Err(name_error(format!("struct '{}' has no method '{}'", struct_name, method.value), method))
+        };
+        if on_struct && found.takes_self {
+            return Err(type_error(format!("method '{}' has to be called on a '{}'", method.value, struct_name), method)
+                .with_note(format!("methods that take `self` are called on a value, like `value.{}()`", method.value)))
         }
-        values_to_eval[0 as usize]
+        if !on_struct && !found.takes_self {
+            return Err(type_error(format!("'{}' does not take `self` and has to be called on the struct", method.value), method)
+                .with_note(format!("like `{}.{}()`", struct_name, method.value)))
+        }
+        self.arguments_check(method, &found.arguments, func_args)?;
+        Ok(found.return_type.clone())
     }
 
-    fn parse(&mut self) -> ParseTypes {
-        let mut to_eval = vec![];
-        for token in &self.to_parse {
-            // converts tokens to parse types for easier evaluation
-            if token.token_type != TokenType::Identifier {
-                to_eval.push((token_type_to_parse_type(token).expect(&*format!("{:?}", token)), token.x, token.y))
-            } else {
-                if self.defined_var.contains_key(&token.value){
-                    let var = var_types_to_parse_type(self.defined_var.get(&token.value).unwrap()).unwrap();
-                    to_eval.push((var, token.x, token.y));
-                } else if self.defined_function.contains_key(&token.value) {
-                    let referred_function = self.defined_function.get(&token.value).unwrap();
-                    if referred_function.1.len() > 0 {
-                        unimplemented!()
-                    } else {
+    fn arguments_check(&self, func_name: &Token, expected: &[ArgTypes], func_args: &[Expr]) -> Result<(), Diagnostic> {
+        // a variadic argument is last and takes any number of arguments of its type, none too
+        let (fixed, variadic) = match expected.split_last() {
+            Some((ArgTypes::Variadic(element_type), fixed)) => (fixed, Some(&**element_type)),
+            _ => (expected, None)
+        };
+        if variadic.is_some() && func_args.len() < fixed.len() {
+            return Err(type_error(format!("expected at least {} arguments {} were given", fixed.len(), func_args.len()), func_name))
+        }
+        if variadic.is_none() && func_args.len() != fixed.len() {
+            return Err(type_error(format!("expected {} arguments {} were given", expected.len(), func_args.len()), func_name))
+        }
 
-                    }
+        for (given_argument, expected_argument_type) in func_args.iter().zip(fixed.iter().chain(variadic.into_iter().cycle())) {
+            let given_type = match arg_type_to_var_type(expected_argument_type) {
+                Some(expected_type) => self.eval_as(given_argument, &expected_type)?,
+                None => self.eval(given_argument)?
+            };
+            if given_type == VarTypes::Error {
+                continue
+            }
 
-                } else {
-                    unimplemented!("{:?}", token)
-                }
+            if !accepts(expected_argument_type, &given_type) {
+                return Err(type_error(format!("Expected {:?} got {:?}", expected_argument_type, given_type), given_argument.token()))
             }
         }
-        self.eval(to_eval).0
+        Ok(())
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub(crate) enum ArgTypes {
+#[derive(Clone, PartialEq)]
+pub enum ArgTypes {
     // types for function arguments
     Int,
+    Sized(IntType),
+    BigInt,
     Str,
+    Char,
     Bool,
     Float,
     Any,
-    Struct,
+    // element type, `Array(Any)` takes every array
+    Array(Box<ArgTypes>),
+    Struct(String),
+    // an argument of any of the types, like `len` taking arrays and strings
+    OneOf(Vec<ArgTypes>),
+    // any number of arguments of the type, only as the last argument
     Variadic(Box<ArgTypes>)
 }
 
+impl fmt::Debug for ArgTypes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // written like VarTypes so errors read the same
+        match self {
+            ArgTypes::Int => write!(f, "Int"),
+            ArgTypes::Sized(int_type) => write!(f, "{:?}", int_type),
+            ArgTypes::BigInt => write!(f, "BigInt"),
+            ArgTypes::Str => write!(f, "Str"),
+            ArgTypes::Char => write!(f, "Char"),
+            ArgTypes::Bool => write!(f, "Bool"),
+            ArgTypes::Float => write!(f, "Float"),
+            ArgTypes::Any => write!(f, "Any"),
+            ArgTypes::Array(element_type) => write!(f, "[{:?}]", element_type),
+            ArgTypes::Struct(name) => write!(f, "{}", name),
+            ArgTypes::OneOf(options) => {
+                let options: Vec<String> = options.iter().map(|option| format!("{:?}", option)).collect();
+                write!(f, "{}", options.join(" or "))
+            },
+            ArgTypes::Variadic(element_type) => write!(f, "Variadic({:?})", element_type),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Checker
 {
     defined_var: HashMap<String, VarTypes>,
-    defined_struct: HashMap<String, VarTypes>,
+    defined_struct: HashMap<String, StructType>,
     //                                 args type      return type
     defined_function: HashMap<String, (Vec<ArgTypes>, Vec<VarTypes>)>,
-    removed: Vec<String>
+    removed: Vec<String>,
+    // every error found so far, checking goes on after one
+    errors: Vec<Diagnostic>,
+    // where the variables and functions in scope were declared, for errors
+    declared_var: HashMap<String, Token>,
+    declared_function: HashMap<String, Token>,
+    declared_struct: HashMap<String, Token>,
+    // return type of the function currently being checked, None at the top level
+    current_return_type: Option<Vec<VarTypes>>,
+    // how many loops the current statement is inside of, break and continue need at least one
+    loop_depth: u32
+}
+
+fn block_returns(statements: &[Parsed]) -> bool {
+    // true when every path through the statements ends in a return
+    statements.iter().any(|statement| match statement {
+        Parsed::Return(..) => true,
+        Parsed::Conditions(conditions) => {
+            // only an if chain with an else block covers every path, else blocks get a `true` condition
+            let has_else = conditions.last().map(|last| matches!(last.1, Expr::Bool(true, _))).unwrap_or(false);
+            has_else && conditions.iter().all(|condition| block_returns(&condition.0))
+        },
+        _ => false
+    })
 }
 
 fn var_types_to_arg_type(var: &VarTypes) -> Option<ArgTypes>{
@@ -222,107 +509,165 @@ fn var_types_to_arg_type(var: &VarTypes) -> Option<ArgTypes>{
     match var {
         VarTypes::Bool => Some(ArgTypes::Bool),
         VarTypes::Int =>   Some(ArgTypes::Int),
+        VarTypes::Sized(int_type) => Some(ArgTypes::Sized(*int_type)),
+        VarTypes::BigInt => Some(ArgTypes::BigInt),
         VarTypes::Float => Some(ArgTypes::Float),
         VarTypes::Str => Some(ArgTypes::Str),
+        VarTypes::Char => Some(ArgTypes::Char),
+        VarTypes::Array(element_type) => Some(ArgTypes::Array(Box::new(var_types_to_arg_type(element_type)?))),
+        VarTypes::Struct(name) => Some(ArgTypes::Struct(name.clone())),
         _ => None
     }
 }
 
 impl Checker {
-    pub fn new() -> Self {
+    pub fn new(natives: &Natives) -> Self {
         let mut new = Self {
             defined_var: HashMap::new(),
             defined_struct: HashMap::new(),
             defined_function: HashMap::new(),
-            removed: vec![]
+            removed: vec![],
+            errors: vec![],
+            declared_var: HashMap::new(),
+            declared_function: HashMap::new(),
+            declared_struct: HashMap::new(),
+            current_return_type: None,
+            loop_depth: 0
         };
-        new.defined_function.insert("out".to_string(), (vec![ArgTypes::Any], vec![])); // print function
+        for (name, native) in natives.iter() {
+            new.declare_native(name.clone(), native);
+        }
         new
     }
-    fn variable_check(&mut self, var_name: Token, var_type: VarTypes, var_given_values: Vec<Token>) -> (String, VarTypes){
+    pub fn declare_native(&mut self, name: String, native: &NativeFunction) {
+        self.defined_function.insert(name, (native.arguments.clone(), native.return_type.clone()));
+    }
+    pub fn declare_variable(&mut self, name: String, var_type: VarTypes) {
+        // for variables made outside of a script, they have no declaration to point errors at
+        self.declared_var.remove(&name);
+        self.defined_var.insert(name, var_type);
+    }
+    // the fields of a struct the scripts defined, in the order they are given when constructing
+    pub fn struct_fields(&self, name: &str) -> Option<&[(String, VarTypes)]> {
+        self.defined_struct.get(name).map(|struct_type| struct_type.fields.as_slice())
+    }
+    pub fn forget_positions(&mut self) {
+        // once the source is gone errors can't point at where things were declared in it
+        self.declared_var.clear();
+        self.declared_function.clear();
+        self.declared_struct.clear();
+    }
+    pub fn expression_check(&self, expression: &Expr) -> Result<Option<VarTypes>, Diagnostic> {
+        // like expression_type but a call to a function that returns nothing is allowed and gives None
+        let type_evaluator = TypeEvaluator::new(
+            &self.defined_var,
+            &self.defined_struct,
+            &self.defined_function,
+            &self.removed);
+        match expression {
+            Expr::Call(func_name, args) => Ok(type_evaluator.call_check(func_name, args)?.first().cloned()),
+            Expr::MethodCall(receiver, method, args) => Ok(type_evaluator.method_check(receiver, method, args)?.first().cloned()),
+            expression => type_evaluator.eval(expression).map(Some)
+        }
+    }
+    fn expression_type(&self, expression: &Expr) -> Result<VarTypes, Diagnostic> {
+        let type_evaluator = TypeEvaluator::new(
+            &self.defined_var,
+            &self.defined_struct,
+            &self.defined_function,
+            &self.removed);
+        type_evaluator.eval(expression)
+    }
+    fn expect_type(&self, expression: &Expr, expected: VarTypes, position: &Token) -> Result<(), Diagnostic> {
+        let type_evaluator = TypeEvaluator::new(
+            &self.defined_var,
+            &self.defined_struct,
+            &self.defined_function,
+            &self.removed);
+        let type_evaluator_return = type_evaluator.eval_as(expression, &expected)?;
+
+        // checker for return evaluation type and expected type
+        if !compatible(&type_evaluator_return, &expected) {
+            let error = type_error(format!("expression result in '{:?}' and not '{:?}'", type_evaluator_return, expected), position);
+            return Err(match (&type_evaluator_return, &expected) {
+                (VarTypes::Int, VarTypes::Sized(_) | VarTypes::BigInt) => {
+                    let type_name = format!("{:?}", expected).to_lowercase();
+                    error.with_note(format!("only int literals become a '{:?}' on their own, other ints need `as {}`", expected, type_name))
+                },
+                (VarTypes::BigInt, VarTypes::Int) => error
+                    .with_note(format!("ints have to be between {} and {}, larger ones are a 'BigInt'", i128::MIN, i128::MAX)),
+                _ => error
+            })
+        }
+        Ok(())
+    }
+    fn record(&mut self, result: Result<(), Diagnostic>) {
+        if let Err(error) = result {
+            self.errors.push(error)
+        }
+    }
+    fn type_check(&self, var_type: &VarTypes, position: &Token) -> Result<(), Diagnostic> {
+        // struct types have to be defined somewhere in the program
+        match var_type {
+            VarTypes::Struct(name) if !self.defined_struct.contains_key(name) => {
+                Err(name_error(format!("type '{}' does not exists", name), position))
+            },
+            VarTypes::Array(element_type) => self.type_check(element_type, position),
+            _ => Ok(())
+        }
+    }
+    fn variable_check(&mut self, var_name: Token, var_type: VarTypes, var_given_values: Expr) -> Result<(String, VarTypes), Diagnostic> {
         if self.defined_var.contains_key(&var_name.value){
             // checks if variable name already exists
-            panic!("variable '{}' already exists, error at line {} char {}", var_name.value, var_name.y, var_name.x);
+            return Err(redeclared("variable", &var_name, self.declared_var.get(&var_name.value)))
         }
-        if var_given_values.len() % 2 == 0  {
-            // will tell you if a expression is wrong because its not odd
-            // ex:
-            // 1 + 1 -> odd
-            // (4 % 6) * 4 -> odd
-            // 13 >= 13 -> odd
-
-            let last_ind = var_given_values.len() - 1;
-            let last = var_given_values.get(last_ind).unwrap();
-            panic!("something wrong with expression just doesnt know, at line {} char {}", last.y, last.x)
+        if let Err(error) = self.type_check(&var_type, &var_name) {
+            self.errors.push(error);
+            return Ok((var_name.value.clone(), VarTypes::Error))
         }
-        // creates a new TypeEvaluator
-        let mut type_evaluator = TypeEvaluator::new(
-            var_given_values,
-            self.defined_var.clone(),
-            self.defined_struct.clone(),
-            self.defined_function.clone(),
-            self.removed.clone());
-
-        // runs the evaluator
-        let type_evaluator_return = parse_type_to_var_types(&type_evaluator.parse()).expect("please help me");
-
-        // checker for return evaluation type and var type
-        if type_evaluator_return != var_type {
-            panic!("expression result in '{:?}' and not '{:?}', at line {} char {}", type_evaluator_return, var_type, var_name.y, var_name.x)
+        // a variable with a bad value is still declared so the code after it can be checked
+        match self.expect_type(&var_given_values, var_type.clone(), &var_name) {
+            Ok(()) => Ok((var_name.value.clone(), var_type)),
+            Err(error) => {
+                self.errors.push(error);
+                Ok((var_name.value.clone(), VarTypes::Error))
+            }
         }
-        (var_name.value.clone(), var_type)
     }
 
-    fn var_reassign_check(&mut self, var_name: Token, given_values: Vec<Token>){
-        if !self.defined_var.contains_key(&var_name.value){
+    fn var_reassign_check(&mut self, var_name: Token, given_values: Expr) -> Result<(), Diagnostic> {
+        let Some(var_type) = self.defined_var.get(&var_name.value).cloned() else {
             // wont reassign if variable doesnt exists
-            panic!("variable '{}' does not exists, error at line {} char {}", var_name.value, var_name.y, var_name.x);
-        }
-        let var_type = self.defined_var.get(&var_name.value).unwrap().clone();
-        if given_values.len() % 2 == 0  {
-            // will tell you if a expression is wrong because its not odd
-            // ex:
-            // 1 + 1 -> odd
-            // (4 % 6) * 4 -> odd
-            // 13 >= 13 -> odd
-
-            let last_index_of_given_values = given_values.len() - 1;
-            let last_token_of_given_values = given_values.get(last_index_of_given_values).unwrap();
-            panic!("something wrong with expression just doesnt know, at line {} char {}", last_token_of_given_values.y, last_token_of_given_values.x)
+            return Err(name_error(format!("variable '{}' does not exists", var_name.value), &var_name))
+        };
+        if var_type == VarTypes::Error {
+            return Ok(())
         }
-        // creates a new TypeEvaluator
-        let mut type_evaluator = TypeEvaluator::new(
-            given_values,
-            self.defined_var.clone(),
-            self.defined_struct.clone(),
-            self.defined_function.clone(),
-            self.removed.clone());
-
-        // runs the evaluator
-        let type_evaluator_return = parse_type_to_var_types(&type_evaluator.parse()).expect("please help me");
+        self.expect_type(&given_values, var_type, &var_name)
+    }
 
-        // checker for return evaluation type and var type
-        if type_evaluator_return != var_type {
-            panic!("expression result in '{:?}' and not '{:?}', at line {} char {}", type_evaluator_return, var_type, var_name.y, var_name.x)
+    fn element_reassign_check(&mut self, target: Expr, given_values: Expr) -> Result<(), Diagnostic> {
+        if let Expr::Index(string, _, bracket) = &target {
+            if self.expression_type(string)? == VarTypes::Str {
+                return Err(type_error("the characters of a string can't be changed".to_string(), bracket)
+                    .with_note("a new string can be given to the variable instead".to_string()))
+            }
         }
+        let element_type = self.expression_type(&target)?;
+        self.expect_type(&given_values, element_type, target.token())
+    }
 
+    fn individual_conditional_check(&mut self, compound_statements: Vec<Parsed>, if_condition: Expr) {
+        let condition_result = self.expect_type(&if_condition, VarTypes::Bool, if_condition.token());
+        self.record(condition_result);
+        self.block_check(compound_statements);
     }
 
-    fn individual_conditional_check(&mut self, compound_statements: Vec<Parsed>, if_condition: Vec<Token>, if_position: (u32, u32)){
-        let mut type_evaluator = TypeEvaluator::new(
-            if_condition,
-            self.defined_var.clone(),
-            self.defined_struct.clone(),
-            self.defined_function.clone(),
-            self.removed.clone());
-        let type_evaluator_return = parse_type_to_var_types(&type_evaluator.parse()).expect("please help me");
-        if type_evaluator_return != VarTypes::Bool {
-            panic!("expression result in '{:?}' and not '{:?}', at line {} char {}", type_evaluator_return, VarTypes::Bool, if_position.1, if_position.0)
-        }
+    fn block_check(&mut self, compound_statements: Vec<Parsed>) {
         // scoping
         let mut local_scope = vec![];
         for statement in compound_statements {
-            let return_value = self.individual_check(statement);
+            let return_value = self.statement_check(statement);
             if return_value.1 != 0 {
                 local_scope.push(return_value)
             }
@@ -333,138 +678,305 @@ impl Checker {
                 _ => unimplemented!()
             }
         }
-        // unimplemented!()
     }
 
-    fn func_call_check(&mut self, func_name: Token, func_args: Vec<Token>){
-        if !self.defined_function.contains_key(&func_name.value){
-            panic!("function '{}' does not exists, error at line {} char {}", func_name.value, func_name.y, func_name.x);
+    fn while_check(&mut self, condition: Expr, compound_statements: Vec<Parsed>) {
+        let condition_result = self.expect_type(&condition, VarTypes::Bool, condition.token());
+        self.record(condition_result);
+        self.loop_depth += 1;
+        self.block_check(compound_statements);
+        self.loop_depth -= 1;
+    }
+
+    fn for_check(&mut self, variable: Token, start: Expr, end: Expr, step: Option<Expr>, compound_statements: Vec<Parsed>) {
+        for bound in [Some(start), Some(end), step].into_iter().flatten() {
+            let bound_result = self.expect_type(&bound, VarTypes::Int, bound.token());
+            self.record(bound_result);
         }
-        let function_referred_to = self.defined_function.get(&func_name.value).unwrap();
-        if func_args.len() != function_referred_to.0.len(){
-            panic!("expected {} arguments {} were given, error at line {} char {}", function_referred_to.0.len(), func_args.len(), func_name.y, func_name.x);
+        // the loop variable only exists inside the body
+        let already_exists = self.defined_var.contains_key(&variable.value);
+        if already_exists {
+            let error = redeclared("variable", &variable, self.declared_var.get(&variable.value));
+            self.errors.push(error);
+        } else {
+            self.defined_var.insert(variable.value.clone(), VarTypes::Int);
+            self.declared_var.insert(variable.value.clone(), variable.clone());
+        }
+        self.loop_depth += 1;
+        self.block_check(compound_statements);
+        self.loop_depth -= 1;
+        if !already_exists {
+            self.defined_var.remove(&variable.value);
+            self.removed.push(variable.value);
         }
+    }
 
-        for ind in 0..func_args.len() {
-            let given_argument = func_args.get(ind).unwrap();
-            let expected_argument_type = function_referred_to.0.get(ind).unwrap();
-            if given_argument.is_data_type(){
-                match expected_argument_type {
-                    ArgTypes::Int => {
-                        if  data_token_type_to_types(&given_argument.token_type).unwrap() != VarTypes::Int {
-                            panic!("Expected {:?} got {:?}, error at line {} char {}", expected_argument_type, data_token_type_to_types(&given_argument.token_type), given_argument.y, given_argument.x);
-                        }
-                    },
-                    ArgTypes::Str => {
-                        if  data_token_type_to_types(&given_argument.token_type).unwrap() != VarTypes::Str {
-                            panic!("Expected {:?} got {:?}, error at line {} char {}", expected_argument_type, data_token_type_to_types(&given_argument.token_type), given_argument.y, given_argument.x);
-                        }
-                    },
-                    ArgTypes::Float => {
-                        if  data_token_type_to_types(&given_argument.token_type).unwrap() != VarTypes::Float {
-                            panic!("Expected {:?} got {:?}, error at line {} char {}", expected_argument_type, data_token_type_to_types(&given_argument.token_type), given_argument.y, given_argument.x);
-                        }
-                    },
-                    ArgTypes::Bool => {
-                        if  data_token_type_to_types(&given_argument.token_type).unwrap() != VarTypes::Bool {
-                            panic!("Expected {:?} got {:?}, error at line {} char {}", expected_argument_type, data_token_type_to_types(&given_argument.token_type), given_argument.y, given_argument.x);
-                        }
-                    },
-                    ArgTypes::Any => {
+    fn loop_control_check(&mut self, keyword: Token) -> Result<(), Diagnostic> {
+        if self.loop_depth == 0 {
+            let name = if keyword.token_type == TokenType::Break { "break" } else { "continue" };
+            return Err(Diagnostic::at_token(ErrorKind::Syntax, format!("'{}' outside of a loop", name), &keyword))
+        }
+        Ok(())
+    }
 
-                    },
-                    ArgTypes::Variadic(_) => {
-                        unimplemented!()
-                    },
-                    ArgTypes::Struct => {
-                        unimplemented!()
-                    },
-                }
-            } else if given_argument.token_type == TokenType::Identifier {
-                if self.defined_var.contains_key(&given_argument.value){
-                    let referred_variable_type = self.defined_var.get(&given_argument.value).unwrap().clone();
-
-                    match expected_argument_type {
-                        ArgTypes::Int => {
-                            if  referred_variable_type != VarTypes::Int {
-                                panic!("Expected {:?} got {:?}, error at line {} char {}", expected_argument_type, data_token_type_to_types(&given_argument.token_type), given_argument.y, given_argument.x);
-                            }
-                        },
-                        ArgTypes::Str => {
-                            if  referred_variable_type != VarTypes::Str {
-                                panic!("Expected {:?} got {:?}, error at line {} char {}", expected_argument_type, data_token_type_to_types(&given_argument.token_type), given_argument.y, given_argument.x);
-                            }
-                        },
-                        ArgTypes::Float => {
-                            if  referred_variable_type != VarTypes::Float {
-                                panic!("Expected {:?} got {:?}, error at line {} char {}", expected_argument_type, data_token_type_to_types(&given_argument.token_type), given_argument.y, given_argument.x);
-                            }
-                        },
-                        ArgTypes::Bool => {
-                            if  referred_variable_type != VarTypes::Bool {
-                                panic!("Expected {:?} got {:?}, error at line {} char {}", expected_argument_type, data_token_type_to_types(&given_argument.token_type), given_argument.y, given_argument.x);
-                            }
-                        },
-                        ArgTypes::Any => {
-
-                        },
-                        ArgTypes::Variadic(_) => {
-                            unimplemented!()
-                        },
-                        ArgTypes::Struct => {
-                            unimplemented!()
-                        },
-                    }
-                } else if self.removed.contains(&given_argument.value) {
-                    panic!("The Variable '{}' is out of scope or undefined, at line {} char {}", given_argument.value.clone(), given_argument.y, given_argument.x);
-                }
-                else {
-                    unimplemented!("{:?}", given_argument)
-                }
-            } else {
-                unimplemented!()
+    fn func_call_check(&mut self, func_name: Token, func_args: Vec<Expr>) -> Result<(), Diagnostic> {
+        let type_evaluator = TypeEvaluator::new(
+            &self.defined_var,
+            &self.defined_struct,
+            &self.defined_function,
+            &self.removed);
+        type_evaluator.call_check(&func_name, &func_args)?;
+        Ok(())
+    }
+
+    fn method_call_check(&mut self, call: Expr) -> Result<(), Diagnostic> {
+        self.expression_check(&call)?;
+        Ok(())
+    }
+
+    fn function_signature(&mut self, func_name: &Token, func_args: &[(Token, VarTypes)], return_type: &[VarTypes]) -> Result<(), Diagnostic> {
+        if self.defined_function.contains_key(&func_name.value){
+            return Err(redeclared("function", func_name, self.declared_function.get(&func_name.value)))
+        }
+        if self.defined_struct.contains_key(&func_name.value){
+            return Err(redeclared("struct", func_name, self.declared_struct.get(&func_name.value)))
+        }
+        self.declared_function.insert(func_name.value.clone(), func_name.clone());
+        let argument_types = func_args.iter().map(|argument| var_types_to_arg_type(&argument.1).unwrap()).collect();
+        self.defined_function.insert(func_name.value.clone(), (argument_types, return_type.to_vec()));
+
+        // the function is still registered with unknown types so its calls aren't reported too
+        for (argument_name, argument_type) in func_args {
+            self.type_check(argument_type, argument_name)?;
+        }
+        for returned in return_type {
+            self.type_check(returned, func_name)?;
+        }
+        Ok(())
+    }
+
+    fn struct_signature(&mut self, struct_name: &Token, fields: &[(Token, VarTypes)]) -> Result<(), Diagnostic> {
+        if self.defined_struct.contains_key(&struct_name.value){
+            return Err(redeclared("struct", struct_name, self.declared_struct.get(&struct_name.value)))
+        }
+        if self.defined_function.contains_key(&struct_name.value){
+            return Err(redeclared("function", struct_name, self.declared_function.get(&struct_name.value)))
+        }
+        let mut struct_type = StructType { fields: vec![], methods: HashMap::new() };
+        let mut declared_fields: HashMap<String, Token> = HashMap::new();
+        for (field_name, field_type) in fields {
+            if declared_fields.contains_key(&field_name.value) {
+                let error = redeclared("field", field_name, declared_fields.get(&field_name.value));
+                self.errors.push(error);
+                continue
+            }
+            declared_fields.insert(field_name.value.clone(), field_name.clone());
+            struct_type.fields.push((field_name.value.clone(), field_type.clone()));
+        }
+        self.declared_struct.insert(struct_name.value.clone(), struct_name.clone());
+        self.defined_struct.insert(struct_name.value.clone(), struct_type);
+        Ok(())
+    }
+
+    fn impl_signature(&mut self, struct_name: &Token, functions: &[Parsed]) -> Result<(), Diagnostic> {
+        if !self.defined_struct.contains_key(&struct_name.value) {
+            return Err(name_error(format!("struct '{}' does not exists", struct_name.value), struct_name))
+        }
+        for function in functions {
+            let Parsed::FunctionDefinition(func_name, func_args, return_type, _) = function else {
+                unreachable!("the parser only allows functions in impl blocks")
+            };
+            let method_result = self.method_signature(&struct_name.value, func_name, func_args, return_type);
+            self.record(method_result);
+        }
+        Ok(())
+    }
+
+    fn method_signature(&mut self, struct_name: &str, func_name: &Token, func_args: &[(Token, VarTypes)], return_type: &[VarTypes]) -> Result<(), Diagnostic> {
+        let methods = &self.defined_struct[struct_name].methods;
+        if let Some(first) = methods.get(&func_name.value) {
+            return Err(redeclared("method", func_name, Some(&first.declared)))
+        }
+        let self_type = VarTypes::Struct(struct_name.to_string());
+        let takes_self = func_args.first().is_some_and(|argument| argument.0.value == "self" && argument.1 == self_type);
+        let arguments = &func_args[takes_self as usize..];
+        let method = Method {
+            takes_self,
+            arguments: arguments.iter().map(|argument| var_types_to_arg_type(&argument.1).unwrap()).collect(),
+            return_type: return_type.to_vec(),
+            declared: func_name.clone()
+        };
+        self.defined_struct.get_mut(struct_name).unwrap().methods.insert(func_name.value.clone(), method);
+
+        for (argument_name, argument_type) in arguments {
+            self.type_check(argument_type, argument_name)?;
+        }
+        for returned in return_type {
+            self.type_check(returned, func_name)?;
+        }
+        Ok(())
+    }
+
+    fn impl_check(&mut self, functions: Vec<Parsed>) {
+        for function in functions {
+            if let Parsed::FunctionDefinition(func_name, args, return_type, body) = function {
+                self.function_check(func_name, args, return_type, body)
             }
         }
     }
-    fn condition_check(&mut self, conditions: Vec<(Vec<Parsed>, Vec<Token>, (u32, u32))>){
+
+    fn struct_check(&mut self, struct_name: Token, fields: Vec<(Token, VarTypes)>) {
+        // field types are checked once every struct is known, so structs can use ones defined after them
+        for (field_name, field_type) in fields {
+            let type_result = self.type_check(&field_type, &field_name);
+            if type_result.is_ok() && self.contains_struct(&field_type, &struct_name.value, &mut HashSet::new()) {
+                self.errors.push(type_error(format!("'{}' can't contain itself through '{}'", struct_name.value, field_name.value), &field_name)
+                    .with_note(format!("a value of '{}' could never be built, an array like `[{}]` can hold it", struct_name.value, struct_name.value)));
+                continue
+            }
+            self.record(type_result);
+        }
+    }
+
+    fn contains_struct(&self, field_type: &VarTypes, target: &str, seen: &mut HashSet<String>) -> bool {
+        // through fields that always hold a value, arrays can be empty so they don't count
+        let VarTypes::Struct(name) = field_type else {
+            return false
+        };
+        if name == target {
+            return true
+        }
+        if !seen.insert(name.clone()) {
+            return false
+        }
+        let Some(struct_type) = self.defined_struct.get(name) else {
+            return false
+        };
+        struct_type.fields.iter().any(|(_, field_type)| self.contains_struct(field_type, target, seen))
+    }
+
+    fn function_check(&mut self, func_name: Token, func_args: Vec<(Token, VarTypes)>, return_type: Vec<VarTypes>, body: Vec<Parsed>) {
+        // functions only see their own arguments, so the body is checked in a fresh variable scope
+        let mut arguments = HashMap::new();
+        let mut declared_arguments: HashMap<String, Token> = HashMap::new();
+        for (argument_name, argument_type) in func_args {
+            if arguments.contains_key(&argument_name.value) {
+                let error = redeclared("argument", &argument_name, declared_arguments.get(&argument_name.value));
+                self.errors.push(error);
+                continue
+            }
+            arguments.insert(argument_name.value.clone(), argument_type);
+            declared_arguments.insert(argument_name.value.clone(), argument_name);
+        }
+        let outer_variables = std::mem::replace(&mut self.defined_var, arguments);
+        let outer_declared = std::mem::replace(&mut self.declared_var, declared_arguments);
+        let outer_removed = std::mem::take(&mut self.removed);
+        let outer_return_type = self.current_return_type.replace(return_type.clone());
+        let outer_loop_depth = std::mem::replace(&mut self.loop_depth, 0);
+
+        for statement in body.clone() {
+            self.statement_check(statement);
+        }
+        if !return_type.is_empty() && !block_returns(&body) {
+            self.errors.push(type_error(format!("function '{}' does not return '{:?}' on every path", func_name.value, return_type[0]), &func_name)
+                .with_note("an if chain only covers every path when it ends in an else block".to_string()))
+        }
+
+        self.defined_var = outer_variables;
+        self.declared_var = outer_declared;
+        self.removed = outer_removed;
+        self.current_return_type = outer_return_type;
+        self.loop_depth = outer_loop_depth;
+    }
+
+    fn return_check(&mut self, return_token: Token, values: Option<Expr>) -> Result<(), Diagnostic> {
+        let expected = match &self.current_return_type {
+            Some(expected) => expected.clone(),
+            None => return Err(Diagnostic::at_token(ErrorKind::Syntax, "return outside of a function".to_string(), &return_token))
+        };
+        let Some(values) = values else {
+            if !expected.is_empty() {
+                return Err(type_error(format!("expected a '{:?}' to be returned", expected[0]), &return_token))
+            }
+            return Ok(())
+        };
+        if expected.is_empty() {
+            return Err(type_error("function does not return a value".to_string(), &return_token))
+        }
+        self.expect_type(&values, expected[0].clone(), &return_token)
+    }
+
+    fn condition_check(&mut self, conditions: Vec<(Vec<Parsed>, Expr, (u32, u32))>) {
         for condition in conditions{
-            self.individual_conditional_check(condition.0, condition.1, condition.2)
+            self.individual_conditional_check(condition.0, condition.1)
         }
     }
 
-    fn individual_check(&mut self, to_check: Parsed) -> (String, u32) {
+    fn individual_check(&mut self, to_check: Parsed) -> Result<(String, u32), Diagnostic> {
         match to_check {
             Parsed::VariableAssignment(name, var_type, values) => {
-                let return_value = self.variable_check(name, var_type, values);
+                let return_value = self.variable_check(name.clone(), var_type, values)?;
                 self.defined_var.insert(return_value.0.clone(), return_value.1);
-                (return_value.0, 1)
-            },
-            Parsed::Conditions(conditions) => {
-                self.condition_check(conditions);
-               ("".to_string(), 0)
+                self.declared_var.insert(return_value.0.clone(), name);
+                return Ok((return_value.0, 1))
             },
-            Parsed::FuncCall(func_name, Args) => {
-                self.func_call_check(func_name, Args);
+            Parsed::Conditions(conditions) => self.condition_check(conditions),
+            Parsed::FuncCall(func_name, args) => self.func_call_check(func_name, args)?,
+            Parsed::FunctionDefinition(func_name, args, return_type, body) => self.function_check(func_name, args, return_type, body),
+            Parsed::Return(return_token, values) => self.return_check(return_token, values)?,
+            Parsed::While(condition, body, _) => self.while_check(condition, body),
+            Parsed::For(variable, start, end, step, body) => self.for_check(variable, start, end, step, body),
+            Parsed::Break(keyword) | Parsed::Continue(keyword) => self.loop_control_check(keyword)?,
+            Parsed::VariableReassignment(name, values) => self.var_reassign_check(name, values)?,
+            Parsed::ElementReassignment(target, values) => self.element_reassign_check(target, values)?,
+            Parsed::StructDefinition(struct_name, fields) => self.struct_check(struct_name, fields),
+            Parsed::Impl(_, functions) => self.impl_check(functions),
+            Parsed::MethodCall(call) => self.method_call_check(call)?,
+            _ => unimplemented!()
+        }
+        Ok(("".to_string(), 0))
+    }
+
+    fn statement_check(&mut self, to_check: Parsed) -> (String, u32) {
+        // an error only stops the statement it is in
+        match self.individual_check(to_check) {
+            Ok(defined) => defined,
+            Err(error) => {
+                self.errors.push(error);
                 ("".to_string(), 0)
             }
-            Parsed::VariableReassignment(name, values) => {
-                self.var_reassign_check(name, values);
-                ("".to_string(), 0)
-            },
-            _ => unimplemented!()
         }
     }
 
-    pub fn check_program(&mut self, program: Parsed) {
-        let mut statements = vec![];
-        if let Parsed::Program(com_statements) = program {
-            statements = com_statements;
-        } else {
+    pub fn check_program(&mut self, program: Parsed) -> Result<(), Vec<Diagnostic>> {
+        let Parsed::Program(statements) = program else {
             panic!("not a program")
-        }
+        };
+        self.check_statements(statements)
+    }
 
+    pub fn check_statements(&mut self, statements: Vec<Parsed>) -> Result<(), Vec<Diagnostic>> {
+        // structs, methods and functions are registered first so they can be used before their definition
+        for statement in &statements {
+            if let Parsed::StructDefinition(struct_name, fields) = statement {
+                let signature_result = self.struct_signature(struct_name, fields);
+                self.record(signature_result);
+            }
+        }
+        for statement in &statements {
+            let signature_result = match statement {
+                Parsed::FunctionDefinition(func_name, args, return_type, _) => self.function_signature(func_name, args, return_type),
+                Parsed::Impl(struct_name, functions) => self.impl_signature(struct_name, functions),
+                _ => continue
+            };
+            self.record(signature_result);
+        }
         for statement in statements {
-            self.individual_check(statement);
+            self.statement_check(statement);
+        }
+        if !self.errors.is_empty() {
+            return Err(std::mem::take(&mut self.errors))
         }
+        Ok(())
     }
-}
\ No newline at end of file
+}
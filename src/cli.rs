@@ -0,0 +1,160 @@
+use std::io::Write;
+use crate::error::{Diagnostic, ErrorKind, Span};
+use crate::interpreter::Overflow;
+use crate::repl::Repl;
+use crate::sl;
+
+const USAGE: &str = "usage: SL <command> [options] <file> [arguments]
+       SL repl [options]
+
+commands:
+    run      run a script, the arguments after the file are passed to it
+    check    parse and type check a script without running it
+    tokens   print the tokens of a script
+    ast      print the parsed statements of a script
+    build    compile a script (not supported yet)
+    repl     read lines from stdin and run them one at a time
+    help     print this message
+
+the file can be - to read the script from stdin
+
+options:
+    --debug              print the tokens and statements before running
+    --error-limit <n>    show at most n errors, 20 by default
+    --overflow <mode>    what int arithmetic does when a result doesn't fit: checked stops with
+                         an error (the default), wrapping goes around, saturating stays at the limit";
+
+// exit codes for when the script itself didn't get to choose one
+const ERROR_EXIT: i32 = 1;
+const USAGE_EXIT: i32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Command {
+    Run,
+    Check,
+    Tokens,
+    Ast,
+    Build,
+    Repl,
+}
+
+struct Options {
+    command: Command,
+    file_path: String,
+    debug: bool,
+    error_limit: usize,
+    overflow: Overflow,
+    // passed on to the script
+    arguments: Vec<String>,
+}
+
+fn usage_error(message: String) -> i32 {
+    eprintln!("{}, see `SL help`", message);
+    USAGE_EXIT
+}
+
+fn parse_arguments(arguments: Vec<String>) -> Result<Options, String> {
+    let mut arguments = arguments.into_iter();
+    let command = match arguments.next().as_deref() {
+        Some("run") => Command::Run,
+        Some("check") => Command::Check,
+        Some("tokens") => Command::Tokens,
+        Some("ast") => Command::Ast,
+        Some("build") => Command::Build,
+        Some("repl") => Command::Repl,
+        Some(unknown) => return Err(format!("unknown command '{}'", unknown)),
+        None => return Err("expected a command".to_string())
+    };
+
+    let mut debug = false;
+    let mut error_limit = sl::ERROR_LIMIT;
+    let mut overflow = Overflow::Checked;
+    // options come before the file, everything after it belongs to the script
+    let file_path = loop {
+        match arguments.next().as_deref() {
+            Some("--debug") => debug = true,
+            Some("--error-limit") => {
+                error_limit = match arguments.next().map(|limit| limit.parse::<usize>()) {
+                    Some(Ok(limit)) if limit > 0 => limit,
+                    _ => return Err("--error-limit expects a number above 0".to_string())
+                }
+            },
+            Some("--overflow") => {
+                overflow = match arguments.next().as_deref() {
+                    Some("checked") => Overflow::Checked,
+                    Some("wrapping") => Overflow::Wrapping,
+                    Some("saturating") => Overflow::Saturating,
+                    _ => return Err("--overflow expects checked, wrapping or saturating".to_string())
+                }
+            },
+            Some(option) if option.starts_with("--") => return Err(format!("unknown option '{}'", option)),
+            Some(file_path) => break file_path.to_string(),
+            // the repl is the only command without a file
+            None if command == Command::Repl => break String::new(),
+            None => return Err("expected a file".to_string())
+        }
+    };
+    let mut arguments: Vec<String> = arguments.collect();
+    if arguments.first().map(|first| first == "--").unwrap_or(false) {
+        arguments.remove(0);
+    }
+    Ok(Options { command, file_path, debug, error_limit, overflow, arguments })
+}
+
+fn print_all(print: impl FnOnce(&mut dyn Write) -> std::io::Result<()>) -> Result<i32, Vec<Diagnostic>> {
+    // a reader that stops early, like `SL tokens f.sl | head`, is fine, the rest wasn't wanted
+    let mut stdout = std::io::stdout().lock();
+    match print(&mut stdout).and_then(|_| stdout.flush()) {
+        Err(error) if error.kind() != std::io::ErrorKind::BrokenPipe =>
+            Err(vec![Diagnostic::new(ErrorKind::Io, format!("couldn't write the output: {}", error), Span::new(0, 0, 0))]),
+        _ => Ok(0)
+    }
+}
+
+pub fn main(arguments: Vec<String>) -> i32 {
+    // returns the exit code of the process
+    if matches!(arguments.first().map(|first| first.as_str()), Some("help" | "--help" | "-h")) {
+        println!("{}", USAGE);
+        return 0
+    }
+    let options = match parse_arguments(arguments) {
+        Ok(options) => options,
+        Err(message) => return usage_error(message)
+    };
+
+    if options.command == Command::Repl {
+        if !options.file_path.is_empty() {
+            return usage_error("the repl doesn't take a file, use `:load` inside it".to_string())
+        }
+        Repl::new(options.error_limit, options.overflow).run();
+        return 0
+    }
+
+    let source = match sl::read_source(&options.file_path) {
+        Ok(source) => source,
+        Err(errors) => {
+            sl::report(errors, &options.file_path, "", options.error_limit);
+            return ERROR_EXIT
+        }
+    };
+    let result = match options.command {
+        Command::Run => sl::run(source.clone(), options.debug, options.arguments, options.overflow),
+        Command::Check => sl::pre_compile(source.clone(), options.debug).map(|_| 0),
+        Command::Tokens => sl::tokens(source.clone()).and_then(|tokens| print_all(|stdout| {
+            for token in tokens {
+                writeln!(stdout, "{:?}", token)?
+            }
+            Ok(())
+        })),
+        Command::Ast => sl::parse(source.clone(), false).and_then(|parsed| print_all(|stdout| writeln!(stdout, "{:#?}", parsed))),
+        Command::Build => sl::compile(source.clone()).map(|_| 0),
+        Command::Repl => unreachable!(),
+    };
+    match result {
+        Ok(code) => code,
+        Err(errors) => {
+            sl::report(errors, &options.file_path, &source, options.error_limit);
+            ERROR_EXIT
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use crate::check::{ArgTypes, Checker};
+use crate::error::{Diagnostic, ErrorKind, Span};
+use crate::interpreter::{fits, Interpreter, Overflow, Value};
+use crate::native::{Context, Input, NativeFunction, Natives, Output};
+use crate::parser::{IntType, Parsed, Parser, VarTypes};
+
+// keeps the variables and functions of every script it evaluated, like the repl
+//
+// errors roll back everything the failed `eval` did, `exit(code)` in a script
+// is returned as an error of kind `ErrorKind::Exit(code)` and keeps what ran before it
+//
+// it can't be sent to another thread, a clone shares its outputs and registered functions
+#[derive(Clone)]
+pub struct Engine {
+    checker: Checker,
+    interpreter: Interpreter,
+}
+
+fn first_error(mut errors: Vec<Diagnostic>) -> Diagnostic {
+    // only one error is returned, the note says how many were found
+    let more = errors.len() - 1;
+    let error = errors.swap_remove(0);
+    match more {
+        0 => error,
+        1 => error.with_note("1 more error was found".to_string()),
+        more => error.with_note(format!("{} more errors were found", more))
+    }
+}
+
+fn out_of_range(value: &Value) -> Option<(i128, IntType)> {
+    // a sized int somewhere in the value that doesn't fit in its type
+    match value {
+        Value::Sized(value, int_type) if !int_type.contains(*value) => Some((*value, *int_type)),
+        Value::Array(elements) => elements.iter().find_map(out_of_range),
+        Value::Struct(_, fields) => fields.iter().find_map(|(_, field)| out_of_range(field)),
+        _ => None
+    }
+}
+
+fn struct_mismatch(checker: &Checker, value: &Value) -> Option<Diagnostic> {
+    // a struct somewhere in the value that isn't one the scripts defined, scripts trust their field types
+    let mismatch = |message: String| Diagnostic::new(ErrorKind::Type, message, Span::new(0, 0, 0));
+    match value {
+        Value::Array(elements) => elements.iter().find_map(|element| struct_mismatch(checker, element)),
+        Value::Struct(name, fields) => {
+            let Some(declared) = checker.struct_fields(name) else {
+                return Some(mismatch(format!("there is no struct '{}'", name)))
+            };
+            let written = |fields: Vec<String>| fields.join(", ");
+            let same_names = fields.len() == declared.len() && fields.iter().zip(declared).all(|(given, declared)| given.0 == declared.0);
+            if !same_names {
+                return Some(mismatch(format!("'{}' was given the fields {}", name, written(fields.iter().map(|field| field.0.clone()).collect())))
+                    .with_note(format!("'{}' has the fields {}, in that order", name, written(declared.iter().map(|field| field.0.clone()).collect()))))
+            }
+            for ((field_name, field_value), (_, field_type)) in fields.iter().zip(declared) {
+                if !fits(field_value, field_type) {
+                    return Some(mismatch(format!("field '{}' of '{}' has to be a '{:?}', it was given {:?}", field_name, name, field_type, field_value)))
+                }
+                if let Some(error) = struct_mismatch(checker, field_value) {
+                    return Some(error)
+                }
+            }
+            None
+        },
+        _ => None
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        Self::with_natives(Natives::standard())
+    }
+    // scripts can only call the given functions, `Natives::standard()` has `out`, `arg`, `arg_count`, `len` and `exit`
+    pub fn with_natives(natives: Natives) -> Self {
+        Self {
+            checker: Checker::new(&natives),
+            interpreter: Interpreter::new(Parsed::Program(vec![]), natives)
+        }
+    }
+    // runs statements and gives the value of the expression at the end, if there is one
+    //
+    // `int a <- 2; a * 3` gives `Value::Int(6)`, `int a <- 2;` gives `Value::Void`
+    pub fn eval(&mut self, source: &str) -> Result<Value, Diagnostic> {
+        self.run(source).map_err(first_error)
+    }
+    // like `eval` but with every error that was found, for the repl to show them all
+    pub(crate) fn run(&mut self, source: &str) -> Result<Value, Vec<Diagnostic>> {
+        let saved = (self.checker.clone(), self.interpreter.clone());
+        let result = self.run_source(source);
+        if result.as_ref().is_err_and(|errors| !errors.iter().all(|error| matches!(error.kind, ErrorKind::Exit(_)))) {
+            (self.checker, self.interpreter) = saved;
+        } else {
+            // errors of later calls are rendered with their own source, they can't point into this one
+            self.checker.forget_positions();
+        }
+        result
+    }
+    fn run_source(&mut self, source: &str) -> Result<Value, Vec<Diagnostic>> {
+        let (Parsed::Program(statements), tail) = Parser::new(false).parse_with_tail(source.to_string())? else {
+            unreachable!()
+        };
+        self.checker.check_statements(statements.clone())?;
+        if let Some(tail) = &tail {
+            self.checker.expression_check(tail).map_err(|error| vec![error])?;
+        }
+
+        self.interpreter.run_statements(statements).map_err(|error| vec![error])?;
+        match tail {
+            Some(tail) => Ok(self.interpreter.evaluate_expression(&tail).map_err(|error| vec![error])?.unwrap_or(Value::Void)),
+            None => Ok(Value::Void)
+        }
+    }
+    // the type an expression would have without running it, None for calls that return nothing
+    pub fn type_of(&self, expression: &str) -> Result<Option<VarTypes>, Diagnostic> {
+        let expression = Parser::new(false).parse_expression_text(expression.to_string()).map_err(first_error)?;
+        self.checker.expression_check(&expression)
+    }
+    // makes a variable scripts can read and change, replacing one with the same name
+    pub fn set_global(&mut self, name: &str, value: Value) -> Result<(), Diagnostic> {
+        let Some(var_type) = value.var_type() else {
+            return Err(Diagnostic::new(ErrorKind::Type, format!("the type of the value given to '{}' can't be told", name), Span::new(0, 0, 0))
+                .with_note("void, empty arrays and arrays with elements of different types have no type".to_string()))
+        };
+        if let Some((value, int_type)) = out_of_range(&value) {
+            return Err(Diagnostic::new(ErrorKind::Type, format!("{} given to '{}' doesn't fit in '{:?}'", value, name, int_type), Span::new(0, 0, 0))
+                .with_note(format!("'{:?}' goes from {} to {}", int_type, int_type.min(), int_type.max())))
+        }
+        if let Some(error) = struct_mismatch(&self.checker, &value) {
+            return Err(error.with_note(format!("in the value given to '{}'", name)))
+        }
+        self.checker.declare_variable(name.to_string(), var_type.clone());
+        self.interpreter.set_variable(name.to_string(), value, var_type);
+        Ok(())
+    }
+    // lets scripts call a rust function, `Value::Void` is what functions without a return type give back
+    //
+    // engine.register_function("twice", vec![ArgTypes::Int], Some(VarTypes::Int), |_, arguments| match arguments[0] {
+    //     Value::Int(value) => Ok(Value::Int(value * 2)),
+    //     _ => unreachable!()
+    // });
+    pub fn register_function<F>(&mut self, name: &str, arguments: Vec<ArgTypes>, return_type: Option<VarTypes>, function: F)
+        where F: Fn(&mut Context, Vec<Value>) -> Result<Value, Diagnostic> + 'static
+    {
+        let native = NativeFunction::new(arguments, return_type, function);
+        self.checker.declare_native(name.to_string(), &native);
+        self.interpreter.add_native(name.to_string(), native);
+    }
+    // what `out` writes to, stdout by default
+    //
+    // let output = Rc::new(RefCell::new(Vec::new()));
+    // engine.set_output(output.clone());
+    // engine.eval("out(1);")?;
+    // assert_eq!(output.borrow().as_slice(), b"1\n");
+    pub fn set_output(&mut self, output: Output) {
+        self.interpreter.context().set_output(output);
+    }
+    // stderr by default, for natives that report something without failing
+    pub fn set_error_output(&mut self, error_output: Output) {
+        self.interpreter.context().set_error_output(error_output);
+    }
+    // stdin by default, for natives that read input
+    pub fn set_input(&mut self, input: Input) {
+        self.interpreter.context().set_input(input);
+    }
+    // `Overflow::Checked` by default, which makes an int result that doesn't fit an error
+    pub fn set_overflow(&mut self, overflow: Overflow) {
+        self.interpreter.set_overflow(overflow);
+    }
+    // how deep calls can go before a script stops with a runtime error, `CALL_LIMIT` (256) by default
+    //
+    // every level takes stack of the thread running the engine, about 7 kB in release builds and
+    // 40 kB in debug builds, so a higher limit needs a thread with a bigger stack
+    pub fn set_call_limit(&mut self, call_limit: usize) {
+        self.interpreter.set_call_limit(call_limit);
+    }
+    pub fn get_global(&self, name: &str) -> Option<Value> {
+        self.interpreter.variable(name)
+    }
+    // every variable with its type, sorted by name
+    pub fn globals(&self) -> Vec<(String, VarTypes, Value)> {
+        self.interpreter.variables()
+    }
+}
@@ -1,13 +1,242 @@
+use std::fmt;
+use std::io::IsTerminal;
+use crate::lexer::Token;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorKind {
+    // the source couldn't be read
+    Io,
+    // lexer and parser
+    Syntax,
+    // names that are undefined, out of scope or defined twice
+    Name,
+    // checker
+    Type,
+    // interpreter
+    Runtime,
+    // features that don't exist yet
+    Unsupported,
+    // not an error, `exit(code)` unwinds the interpreter with it
+    Exit(i32),
+}
+
+impl ErrorKind {
+    fn title(&self) -> &'static str {
+        match self {
+            ErrorKind::Io => "io error",
+            ErrorKind::Syntax => "syntax error",
+            ErrorKind::Name => "name error",
+            ErrorKind::Type => "type error",
+            ErrorKind::Runtime => "runtime error",
+            ErrorKind::Unsupported => "unsupported",
+            ErrorKind::Exit(_) => "exit",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+    pub length: u32,
+}
+
+impl Span {
+    pub fn new(line: u32, column: u32, length: u32) -> Self {
+        Self {
+            file: "".to_string(),
+            line,
+            column,
+            length
+        }
+    }
+    pub fn from_token(token: &Token) -> Self {
+        Self::new(token.y, token.x, token.length)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub kind: ErrorKind,
+    pub message: String,
+    pub span: Span,
+    // short text under the primary span
+    pub label: Option<String>,
+    // other places that explain the error, like where something was first declared
+    pub secondary: Vec<Label>,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn new(kind: ErrorKind, message: String, span: Span) -> Self {
+        Self {
+            kind,
+            message,
+            span,
+            label: None,
+            secondary: vec![],
+            notes: vec![]
+        }
+    }
+    pub fn at_token(kind: ErrorKind, message: String, token: &Token) -> Self {
+        Self::new(kind, message, Span::from_token(token))
+    }
+    pub fn with_label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+    pub fn with_secondary(mut self, span: Span, message: String) -> Self {
+        self.secondary.push(Label { span, message });
+        self
+    }
+    pub fn with_note(mut self, note: String) -> Self {
+        self.notes.push(note);
+        self
+    }
+    pub fn set_file(&mut self, file: String) {
+        for label in &mut self.secondary {
+            label.span.file = file.clone();
+        }
+        self.span.file = file;
+    }
+    pub fn raise(&self, source: &str) {
+        Emitter::new().emit(self, source)
+    }
+}
 
-pub struct Error {
-    error_title: String,
-    error_body: String,
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {}", self.kind.title(), self.message)?;
+        // line 0 means the error isn't about a position in the file
+        if self.span.line == 0 {
+            write!(f, "  --> {}", self.span.file)?;
+        } else {
+            write!(f, "  --> {}:{}:{}", self.span.file, self.span.line, self.span.column)?;
+        }
+        for note in &self.notes {
+            write!(f, "\n  = note: {}", note)?;
+        }
+        Ok(())
+    }
 }
 
-impl Error {
-    fn raise(&self){
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
 
+// renders diagnostics with the source lines they point at
+//
+//  name error: variable 'a' already exists
+//   --> main.sl:2:5
+//    |
+//  1 | int a <- 1;
+//    |     - first declared here
+//  2 | int a <- 2;
+//    |     ^ declared again here
+//
+// colors are only used when stderr is a terminal, secondary labels like
+// "first declared here" can be turned off
+pub struct Emitter {
+    color: bool,
+    secondary_labels: bool,
+}
+
+impl Default for Emitter {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
+impl Emitter {
+    pub fn new() -> Self {
+        Self {
+            color: std::io::stderr().is_terminal(),
+            secondary_labels: true
+        }
+    }
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+    pub fn with_secondary_labels(mut self, secondary_labels: bool) -> Self {
+        self.secondary_labels = secondary_labels;
+        self
+    }
+    fn paint(&self, text: &str, color: &str) -> String {
+        if self.color {
+            format!("{}{}{}", color, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+    pub fn emit(&self, diagnostic: &Diagnostic, source: &str) {
+        eprintln!("{}", self.render(diagnostic, source))
+    }
+    pub fn render(&self, diagnostic: &Diagnostic, source: &str) -> String {
+        let mut out = format!("{}{}\n",
+                              self.paint(diagnostic.kind.title(), RED),
+                              self.paint(&format!(": {}", diagnostic.message), BOLD));
+
+        // (span, label, primary)
+        let mut labels = vec![(&diagnostic.span, diagnostic.label.clone().unwrap_or_default(), true)];
+        if self.secondary_labels {
+            for label in &diagnostic.secondary {
+                labels.push((&label.span, label.message.clone(), false));
+            }
+        }
+        labels.retain(|label| label.0.line != 0);
+        labels.sort_by_key(|label| (label.0.line, label.0.column));
+
+        let width = labels.iter().map(|label| label.0.line.to_string().len()).max().unwrap_or(1);
+        let gutter = self.paint(&format!("{} |", " ".repeat(width)), BLUE);
+
+        if diagnostic.span.line == 0 {
+            out += &format!("{} {}", self.paint(&format!("{}-->", " ".repeat(width)), BLUE), diagnostic.span.file);
+        } else {
+            out += &format!("{} {}:{}:{}", self.paint(&format!("{}-->", " ".repeat(width)), BLUE),
+                            diagnostic.span.file, diagnostic.span.line, diagnostic.span.column);
+        }
+
+        if !labels.is_empty() {
+            let lines: Vec<&str> = source.lines().collect();
+            out += &format!("\n{}", gutter);
+            let mut last_line = None;
+            for (span, message, primary) in &labels {
+                let text = lines.get(span.line as usize - 1).copied().unwrap_or("");
+                if last_line != Some(span.line) {
+                    if last_line.is_some_and(|last| span.line > last + 1) {
+                        out += &format!("\n{}", self.paint("...", BLUE));
+                    }
+                    out += &format!("\n{} {}", self.paint(&format!("{:>width$} |", span.line), BLUE), text);
+                    last_line = Some(span.line);
+                }
+                out += &format!("\n{} {}", gutter, self.underline(span, message, *primary, text));
+            }
+            if !diagnostic.notes.is_empty() {
+                out += &format!("\n{}", gutter);
+            }
+        }
+        for note in &diagnostic.notes {
+            out += &format!("\n{} {} {}", self.paint(&format!("{} =", " ".repeat(width)), BLUE), self.paint("note:", BOLD), note);
+        }
+        out
+    }
+    fn underline(&self, span: &Span, message: &str, primary: bool, text: &str) -> String {
+        // tabs are kept so the marker lines up with the source line however wide tabs are shown
+        let padding: String = text.chars().take(span.column.saturating_sub(1) as usize)
+            .map(|character| if character == '\t' { '\t' } else { ' ' })
+            .collect();
+        let marker = if primary { "^" } else { "-" }.repeat(span.length.max(1) as usize);
+        let color = if primary { RED } else { BLUE };
+        let marked = if message.is_empty() { marker } else { format!("{} {}", marker, message) };
+        format!("{}{}", padding, self.paint(&marked, color))
+    }
+}
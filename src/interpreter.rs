@@ -1,15 +1,23 @@
 use std::collections::HashMap;
-use crate::{Parsed, parser};
-use crate::check::{ArgTypes, Checker};
-use crate::lexer::{Token, TokenType};
-use crate::parser::VarTypes;
-use crate::util::eval::eval_string;
+use std::fmt;
+use std::rc::Rc;
+use crate::ast::Expr;
+use crate::parser::Parsed;
+use crate::lexer::Token;
+use crate::parser::{IntType, VarTypes};
+use crate::error::{Diagnostic, ErrorKind, Span};
+use crate::native::{Context, NativeFunction, Natives};
+use crate::bigint::BigInt;
+use crate::check::{arg_type_to_var_type, ArgTypes};
 
 
+#[derive(Clone)]
 struct Variable {
     name: String,
     value: Value,
-    var_type: VarTypes
+    var_type: VarTypes,
+    // where every char of a string value starts, made by the first `s[i]` so a loop over the string stays linear
+    char_starts: Option<Vec<usize>>
 }
 
 impl Variable {
@@ -17,200 +25,1111 @@ impl Variable {
         Self {
             name,
             value,
-            var_type
+            var_type,
+            char_starts: None
         }
     }
+    // every change goes through here so the char table never belongs to an older string
+    fn value_mut(&mut self) -> &mut Value {
+        self.char_starts = None;
+        &mut self.value
+    }
+}
+
+fn char_starts(text: &str) -> Vec<usize> {
+    text.char_indices().map(|(start, _)| start).collect()
 }
 
+fn char_at(text: &str, starts: &[usize], index: Value, bracket: &Token) -> Result<Value, Diagnostic> {
+    // the index counts characters, not bytes
+    let index = array_index(index, starts.len(), "a string", bracket)?;
+    Ok(Value::Char(text[starts[index]..].chars().next().expect("starts are where chars begin")))
+}
+
+#[derive(Clone)]
 struct Functions {
-    statement: Parsed,
+    statements: Vec<Parsed>,
     name: String,
     arguments: Vec<(String, VarTypes)>,
     return_type: Vec<VarTypes>
 }
 
-fn substring(str: String, start_index: i32, end_index: i32) ->  Option<String>
-{
-    if end_index <= start_index
-    {
-        return None;
+impl Functions {
+    fn new(name: String, arguments: Vec<(String, VarTypes)>, return_type: Vec<VarTypes>, statements: Vec<Parsed>) -> Self {
+        Self {
+            statements,
+            name,
+            arguments,
+            return_type
+        }
     }
-    let substring = (&str[(start_index as usize)..(end_index as usize)]).to_string();
-    Option::from(substring)
+}
+
+// the indexes and field names leading to an element of a variable, `a[1].x` is `[(Some(1), [), (None, x)]`
+type ElementPath = Vec<(Option<Value>, Token)>;
 
+fn function_definition(definition: &Parsed) -> Rc<Functions> {
+    let Parsed::FunctionDefinition(func_name, args, return_type, body) = definition else {
+        panic!("huh? what? Expected a function definition")
+    };
+    let arguments = args.iter().map(|(name, arg_type)| (name.value.clone(), arg_type.clone())).collect();
+    Rc::new(Functions::new(func_name.value.clone(), arguments, return_type.clone(), body.clone()))
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(Clone)]
+struct Structs {
+    // in the order they are given when constructing
+    fields: Rc<[(String, VarTypes)]>,
+    // from the impl blocks of the struct, methods have `self` as their first argument
+    methods: HashMap<String, Rc<Functions>>
+}
+
+#[derive(PartialEq, PartialOrd, Debug, Clone)]
 pub enum Value {
     Int(i128),
+    // a value in the range of its type
+    Sized(i128, IntType),
+    BigInt(BigInt),
     String(String),
+    Char(char),
     Bool(bool),
-    Float(f64)
+    Float(f64),
+    // arrays and structs are copied when they are assigned or passed to a function
+    Array(Vec<Value>),
+    // struct name, fields in the order they were defined
+    Struct(String, Vec<(String, Value)>),
+    // what statements and calls that return nothing evaluate to, scripts never see it
+    Void
+}
+
+impl Value {
+    pub fn var_type(&self) -> Option<VarTypes> {
+        match self {
+            Value::Int(_) => Some(VarTypes::Int),
+            Value::Sized(_, int_type) => Some(VarTypes::Sized(*int_type)),
+            Value::BigInt(_) => Some(VarTypes::BigInt),
+            Value::String(_) => Some(VarTypes::Str),
+            Value::Char(_) => Some(VarTypes::Char),
+            Value::Bool(_) => Some(VarTypes::Bool),
+            Value::Float(_) => Some(VarTypes::Float),
+            // empty arrays and arrays of mixed types have no type
+            Value::Array(elements) => {
+                let element_type = elements.first()?.var_type()?;
+                for element in &elements[1..] {
+                    if element.var_type()? != element_type {
+                        return None
+                    }
+                }
+                Some(VarTypes::Array(Box::new(element_type)))
+            },
+            Value::Struct(name, _) => Some(VarTypes::Struct(name.clone())),
+            Value::Void => None
+        }
+    }
+}
+
+fn write_element(f: &mut fmt::Formatter<'_>, value: &Value) -> fmt::Result {
+    // strings and chars inside arrays and structs get quotes, `["a", "b"]`
+    match value {
+        Value::String(val) => write!(f, "{:?}", val),
+        Value::Char(val) => write!(f, "{:?}", val),
+        value => write!(f, "{}", value)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(val) | Value::Sized(val, _) => write!(f, "{}", val),
+            Value::BigInt(val) => write!(f, "{}", val),
+            Value::String(val) => write!(f, "{}", val),
+            Value::Char(val) => write!(f, "{}", val),
+            Value::Float(val) => write!(f, "{}", val),
+            Value::Bool(val) => write!(f, "{}", val),
+            Value::Array(elements) => {
+                write!(f, "[")?;
+                for (index, element) in elements.iter().enumerate() {
+                    if index != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write_element(f, element)?;
+                }
+                write!(f, "]")
+            },
+            Value::Struct(name, fields) => {
+                // `Point { x: 1, y: 2 }`
+                write!(f, "{} {{", name)?;
+                for (index, (field_name, field_value)) in fields.iter().enumerate() {
+                    if index != 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, " {}: ", field_name)?;
+                    write_element(f, field_value)?;
+                }
+                write!(f, " }}")
+            },
+            Value::Void => write!(f, "void"),
+        }
+    }
+}
+
+fn runtime_error(message: String, token: &Token) -> Diagnostic {
+    Diagnostic::at_token(ErrorKind::Runtime, message, token)
+}
+
+// `indexed` is what the index is into, "an array" or "a string"
+fn array_index(index: Value, length: usize, indexed: &str, bracket: &Token) -> Result<usize, Diagnostic> {
+    let Value::Int(index) = index else {
+        return Err(runtime_error(format!("Expected Int got {:?}", index), bracket))
+    };
+    match usize::try_from(index) {
+        Ok(index) if index < length => Ok(index),
+        _ => Err(runtime_error(format!("index {} is out of bounds for {} of length {}", index, indexed, length), bracket)
+            .with_label("indexed here".to_string()))
+    }
+}
+
+// what int arithmetic does with a result that doesn't fit in an int
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    // stops the script with a runtime error
+    #[default]
+    Checked,
+    // goes around to the other end, the largest int + 1 is the smallest
+    Wrapping,
+    // stays at the largest or smallest int
+    Saturating,
+}
+
+// an int operation for every kind of overflow
+struct IntOperation {
+    checked: fn(i128, i128) -> Option<i128>,
+    wrapping: fn(i128, i128) -> i128,
+    saturating: fn(i128, i128) -> i128,
+}
+
+const ADD: IntOperation = IntOperation { checked: i128::checked_add, wrapping: i128::wrapping_add, saturating: i128::saturating_add };
+const SUBTRACT: IntOperation = IntOperation { checked: i128::checked_sub, wrapping: i128::wrapping_sub, saturating: i128::saturating_sub };
+const MULTIPLY: IntOperation = IntOperation { checked: i128::checked_mul, wrapping: i128::wrapping_mul, saturating: i128::saturating_mul };
+// only the smallest int / -1 overflows, dividing by zero is checked before
+const DIVIDE: IntOperation = IntOperation { checked: i128::checked_div, wrapping: i128::wrapping_div, saturating: i128::saturating_div };
+// the smallest int % -1 is 0, it only overflows in rust
+const MODULO: IntOperation = IntOperation {
+    checked: |left, right| Some(left.wrapping_rem(right)),
+    wrapping: i128::wrapping_rem,
+    saturating: i128::wrapping_rem
+};
+const NEGATE: IntOperation = IntOperation {
+    checked: |_, value| value.checked_neg(),
+    wrapping: |_, value| value.wrapping_neg(),
+    saturating: |_, value| value.saturating_neg()
+};
+const POWER: IntOperation = IntOperation {
+    checked: |base, exponent| int_power(base, exponent, i128::checked_mul),
+    wrapping: |base, exponent| int_power(base, exponent, |left, right| Some(left.wrapping_mul(right))).expect("wrapping never fails"),
+    saturating: |base, exponent| int_power(base, exponent, i128::checked_mul)
+        .unwrap_or(if base < 0 && exponent % 2 == 1 { i128::MIN } else { i128::MAX })
+};
+
+fn int_power(mut base: i128, mut exponent: i128, multiply: fn(i128, i128) -> Option<i128>) -> Option<i128> {
+    // squares the base for every bit of the exponent, the exponent is never negative
+    let mut result: i128 = 1;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = multiply(result, base)?;
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = multiply(base, base)?;
+        }
+    }
+    Some(result)
+}
+
+fn overflow_error(written: String, operator: &Token, int_type: Option<IntType>) -> Diagnostic {
+    match int_type {
+        None => runtime_error(format!("{} doesn't fit in an int", written), operator)
+            .with_note(format!("integers have to be between {} and {}", i128::MIN, i128::MAX)),
+        Some(int_type) => runtime_error(format!("{} doesn't fit in '{:?}'", written, int_type), operator)
+            .with_note(format!("'{:?}' goes from {} to {}", int_type, int_type.min(), int_type.max()))
+    }
+}
+
+fn int_arithmetic(left: i128, right: i128, overflow: Overflow, operation: IntOperation, int_type: Option<IntType>) -> Option<i128> {
+    // sized ints are worked out as an i128 and then made to fit, None when the result doesn't
+    match overflow {
+        Overflow::Checked => (operation.checked)(left, right).filter(|result| int_type.is_none_or(|int_type| int_type.contains(*result))),
+        Overflow::Wrapping => {
+            let result = (operation.wrapping)(left, right);
+            Some(int_type.map_or(result, |int_type| int_type.wrap(result)))
+        },
+        Overflow::Saturating => {
+            let result = (operation.saturating)(left, right);
+            Some(int_type.map_or(result, |int_type| int_type.saturate(result)))
+        }
+    }
+}
+
+fn same_size(left: Value, right: Value) -> (Value, Value) {
+    // int literals next to a sized int or a bigint take its type, the checker made sure they fit
+    match (left, right) {
+        (Value::Int(left), right @ Value::Sized(_, int_type)) => (Value::Sized(left, int_type), right),
+        (left @ Value::Sized(_, int_type), Value::Int(right)) => (left, Value::Sized(right, int_type)),
+        (Value::Int(left), right @ Value::BigInt(_)) => (Value::BigInt(BigInt::from(left)), right),
+        (left @ Value::BigInt(_), Value::Int(right)) => (left, Value::BigInt(BigInt::from(right))),
+        sides => sides
+    }
+}
+
+fn coerce(value: Value, var_type: &VarTypes) -> Value {
+    // int literals stored where a sized int or a bigint is expected become one
+    match (value, var_type) {
+        (Value::Int(value), VarTypes::Sized(int_type)) => Value::Sized(value, *int_type),
+        (Value::Int(value), VarTypes::BigInt) => Value::BigInt(BigInt::from(value)),
+        (Value::Array(elements), VarTypes::Array(element_type)) => {
+            Value::Array(elements.into_iter().map(|element| coerce(element, element_type)).collect())
+        },
+        (value, _) => value
+    }
+}
+
+pub(crate) fn fits(value: &Value, var_type: &VarTypes) -> bool {
+    // whether a value from outside the script has the type it is used as
+    match (value, var_type) {
+        (Value::Array(elements), VarTypes::Array(element_type)) => elements.iter().all(|element| fits(element, element_type)),
+        (Value::Sized(value, int_type), VarTypes::Sized(expected)) => int_type == expected && int_type.contains(*value),
+        (value, var_type) => value.var_type().as_ref() == Some(var_type)
+    }
+}
+
+fn arithmetic(left: Value, right: Value, operator: &Token, overflow: Overflow, int_operation: IntOperation,
+              big_operation: fn(&BigInt, &BigInt) -> BigInt, float_operation: fn(f64, f64) -> f64) -> Result<Value, Diagnostic> {
+    // floats follow IEEE 754, dividing by zero gives inf or NaN, bigints never overflow
+    let written = |left, right| format!("{} {} {}", left, operator.value, right);
+    match same_size(left, right) {
+        (Value::Int(left), Value::Int(right)) => match int_arithmetic(left, right, overflow, int_operation, None) {
+            Some(result) => Ok(Value::Int(result)),
+            None => Err(overflow_error(written(left, right), operator, None))
+        },
+        (Value::Sized(left, int_type), Value::Sized(right, _)) => match int_arithmetic(left, right, overflow, int_operation, Some(int_type)) {
+            Some(result) => Ok(Value::Sized(result, int_type)),
+            None => Err(overflow_error(written(left, right), operator, Some(int_type)))
+        },
+        (Value::BigInt(left), Value::BigInt(right)) => Ok(Value::BigInt(big_operation(&left, &right))),
+        (Value::Float(left), Value::Float(right)) => Ok(Value::Float(float_operation(left, right))),
+        (left, right) => Err(runtime_error(format!("'{}' can't be used on {:?} and {:?}", operator.value, left, right), operator))
+    }
+}
+
+fn as_float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Int(value) | Value::Sized(value, _) => Some(*value as f64),
+        Value::BigInt(value) => Some(value.to_f64()),
+        Value::Float(value) => Some(*value),
+        _ => None
+    }
+}
+
+// about 315000 decimal digits
+const BIG_POWER_BITS: u64 = 1 << 20;
+
+fn big_power(base: &BigInt, exponent: &Value, operator: &Token) -> Result<Value, Diagnostic> {
+    let exponent = match exponent {
+        Value::Int(exponent) | Value::Sized(exponent, _) => BigInt::from(*exponent),
+        Value::BigInt(exponent) => exponent.clone(),
+        exponent => return Err(runtime_error(format!("'**' can't be used on {:?} and {:?}", base, exponent), operator))
+    };
+    if exponent < BigInt::from(0) {
+        return Err(runtime_error(format!("an int can't be raised to the negative power {}", exponent), operator)
+            .with_note("floats can, `2.0 ** -1` is 0.5".to_string()))
+    }
+    // 0, 1 and -1 stay small for every exponent, anything else is kept under BIG_POWER_BITS
+    // so it can't run for hours or out of memory
+    let too_large = || runtime_error(format!("{} ** {} is too large to work out", base, exponent), operator)
+        .with_note(format!("the result of '**' on a bigint can't take more than {} bits", BIG_POWER_BITS));
+    match (base.to_i128(), exponent.to_i128().and_then(|exponent| u32::try_from(exponent).ok())) {
+        (Some(0 | 1), _) if !exponent.is_zero() => Ok(Value::BigInt(base.clone())),
+        (Some(-1), _) => Ok(Value::BigInt(BigInt::from(if exponent.low_bits() % 2 == 0 { 1 } else { -1 }))),
+        // the result has at least this many bits, exactly this many for a power of two
+        (_, Some(small)) if base.bit_length().saturating_sub(1).saturating_mul(small as u64) < BIG_POWER_BITS => Ok(Value::BigInt(base.pow(small))),
+        _ => Err(too_large())
+    }
+}
+
+fn power(base: Value, exponent: Value, operator: &Token, overflow: Overflow) -> Result<Value, Diagnostic> {
+    let (base_value, int_type, exponent_value) = match (&base, &exponent) {
+        (Value::BigInt(base), Value::Int(_) | Value::Sized(..) | Value::BigInt(_)) => return big_power(base, &exponent, operator),
+        (Value::Int(base), Value::Int(exponent) | Value::Sized(exponent, _)) => (*base, None, *exponent),
+        (Value::Sized(base, int_type), Value::Int(exponent) | Value::Sized(exponent, _)) => (*base, Some(*int_type), *exponent),
+        // a float on either side makes it a float
+        _ => return match (as_float(&base), as_float(&exponent)) {
+            (Some(base), Some(exponent)) => Ok(Value::Float(base.powf(exponent))),
+            _ => Err(runtime_error(format!("'**' can't be used on {:?} and {:?}", base, exponent), operator))
+        }
+    };
+    if exponent_value < 0 {
+        return Err(runtime_error(format!("an int can't be raised to the negative power {}", exponent_value), operator)
+            .with_note("floats can, `2.0 ** -1` is 0.5".to_string()))
+    }
+    match (int_arithmetic(base_value, exponent_value, overflow, POWER, int_type), int_type) {
+        (Some(result), None) => Ok(Value::Int(result)),
+        (Some(result), Some(int_type)) => Ok(Value::Sized(result, int_type)),
+        (None, int_type) => Err(overflow_error(format!("{} ** {}", base_value, exponent_value), operator, int_type))
+    }
+}
+
+fn binary(expression: &Expr, left: Value, right: Value, overflow: Overflow) -> Result<Value, Diagnostic> {
+    // both sides are already worked out, this never calls back into the interpreter
+    let is_zero = |value: &Value| matches!(value, Value::Int(0) | Value::Sized(0, _)) || matches!(value, Value::BigInt(value) if value.is_zero());
+    Ok(match expression {
+        Expr::Addition(_, _, operator) => match (left, right) {
+            (Value::String(left), Value::String(right)) => Value::String(left + &right),
+            (left, right) => arithmetic(left, right, operator, overflow, ADD, |a, b| a + b, |a, b| a + b)?
+        },
+        Expr::Subtraction(_, _, operator) => arithmetic(left, right, operator, overflow, SUBTRACT, |a, b| a - b, |a, b| a - b)?,
+        Expr::Multiply(_, _, operator) => arithmetic(left, right, operator, overflow, MULTIPLY, |a, b| a * b, |a, b| a * b)?,
+        Expr::Division(_, _, operator) | Expr::Modulo(_, _, operator) if is_zero(&right) => {
+            return Err(runtime_error("division by zero".to_string(), operator))
+        },
+        Expr::Division(_, _, operator) => arithmetic(left, right, operator, overflow, DIVIDE, |a, b| a / b, |a, b| a / b)?,
+        Expr::Modulo(_, _, operator) => arithmetic(left, right, operator, overflow, MODULO, |a, b| a % b, |a, b| a % b)?,
+        Expr::Power(_, _, operator) => power(left, right, operator, overflow)?,
+        Expr::Comparison(_, _, operator) => {
+            let (left, right) = same_size(left, right);
+            Value::Bool(match &*operator.value {
+                "==" => left == right,
+                "!=" => left != right,
+                ">" => left > right,
+                "<" => left < right,
+                ">=" => left >= right,
+                "<=" => left <= right,
+                _ => unimplemented!("{:?}", operator)
+            })
+        },
+        _ => unreachable!("not a binary expression")
+    })
 }
 
+fn cast(value: Value, cast_type: &VarTypes, operator: &Token) -> Result<Value, Diagnostic> {
+    // an int cast to a smaller one keeps its lowest bits, 300 as u8 is 44
+    // a float cast to an int is cut toward zero and kept in its range, NaN is 0
+    Ok(match (value, cast_type) {
+        (Value::Int(value) | Value::Sized(value, _), VarTypes::Int) => Value::Int(value),
+        (Value::Int(value) | Value::Sized(value, _), VarTypes::Sized(int_type)) => Value::Sized(int_type.wrap(value), *int_type),
+        (Value::Int(value) | Value::Sized(value, _), VarTypes::Float) => Value::Float(value as f64),
+        (Value::Float(value), VarTypes::Float) => Value::Float(value),
+        (Value::Float(value), VarTypes::Int) => Value::Int(value as i128),
+        (Value::Float(value), VarTypes::Sized(int_type)) => Value::Sized(int_type.saturate(value as i128), *int_type),
+        (Value::Int(value) | Value::Sized(value, _), VarTypes::BigInt) => Value::BigInt(BigInt::from(value)),
+        (Value::BigInt(value), VarTypes::BigInt) => Value::BigInt(value),
+        // chars are cast through their code point
+        (Value::Char(value), VarTypes::Char) => Value::Char(value),
+        (Value::Char(value), cast_type) => return cast(Value::Int(value as i128), cast_type, operator),
+        (Value::Int(value) | Value::Sized(value, _), VarTypes::Char) => match u32::try_from(value).ok().and_then(char::from_u32) {
+            Some(character) => Value::Char(character),
+            None => return Err(runtime_error(format!("{} isn't a unicode character", value), operator)
+                .with_note("chars go from 0 to 1114111 (0x10FFFF) without 55296 to 57343 (0xD800 to 0xDFFF)".to_string()))
+        },
+        (Value::BigInt(value), VarTypes::Char) => match value.to_i128() {
+            Some(value) => return cast(Value::Int(value), cast_type, operator),
+            None => return Err(runtime_error(format!("{} isn't a unicode character", value), operator))
+        },
+        (Value::BigInt(value), VarTypes::Int) => Value::Int(value.low_bits()),
+        (Value::BigInt(value), VarTypes::Sized(int_type)) => Value::Sized(int_type.wrap(value.low_bits()), *int_type),
+        (Value::BigInt(value), VarTypes::Float) => Value::Float(value.to_f64()),
+        // a bigint has no largest value to keep inf at
+        (Value::Float(value), VarTypes::BigInt) => match BigInt::from_f64(value) {
+            Some(value) => Value::BigInt(value),
+            None => return Err(runtime_error(format!("{} can't be cast to a bigint", value), operator))
+        },
+        (value, _) => return Err(runtime_error(format!("{:?} can't be cast to '{:?}'", value, cast_type), operator))
+    })
+}
+
+// deep enough for most recursion while fitting in a 2 MB thread stack in release builds,
+// debug builds take several times more stack for every call
+pub const CALL_LIMIT: usize = 256;
+
+#[derive(Clone)]
 pub struct Interpreter {
     program: Vec<Parsed>,
     defined_variable: HashMap<String, Variable>,
-    defined_function: HashMap<String, (u32, Vec<VarTypes>)>,
-    built_in_function: Vec<String>,
-    defined_struct: HashMap<String, ()>
+    defined_function: HashMap<String, Rc<Functions>>,
+    natives: Natives,
+    context: Context,
+    overflow: Overflow,
+    defined_struct: HashMap<String, Structs>,
+    // set by `return`, `break` and `continue` so the blocks being run stop early
+    returning: bool,
+    return_value: Option<Value>,
+    breaking: bool,
+    continuing: bool,
+    // how many function bodies are running, every level takes a few kB of the rust stack
+    call_depth: usize,
+    call_limit: usize
 }
 
 impl Interpreter {
-    pub fn new(prog: Parsed) -> Self {
+    pub fn new(prog: Parsed, natives: Natives) -> Self {
         if let Parsed::Program(program) = prog  {
             Self {
                 program,
                 defined_variable: HashMap::new(),
                 defined_function: HashMap::new(),
-                built_in_function: vec!["out".to_string(), ],
-                defined_struct: HashMap::new()
+                natives,
+                context: Context::new(),
+                overflow: Overflow::Checked,
+                defined_struct: HashMap::new(),
+                returning: false,
+                return_value: None,
+                breaking: false,
+                continuing: false,
+                call_depth: 0,
+                call_limit: CALL_LIMIT
             }
         } else {
             panic!("huh? what? Expected a parsed program")
         }
     }
-    fn evaluate(&self, to_evaluate: Vec<Token>) -> String {
-        let mut to_send_to_eval = "".to_string();
-        for current_token in to_evaluate {
-            if current_token.is_data_type() || current_token.token_type == TokenType::ComparisonOperation || current_token.token_type == TokenType::MathOperation {
-                to_send_to_eval += &*current_token.true_value();
-            } else if current_token.token_type == TokenType::Identifier {
-                if self.defined_variable.contains_key(&current_token.value){
-                    let referred_variable = self.defined_variable.get(&current_token.value).unwrap();
-                    match referred_variable.value.clone() {
-                        Value::String(value) => to_send_to_eval += &*format!("\"{}\"", value),
-                        Value::Int(value) => to_send_to_eval += &*value.to_string(),
-                        Value::Bool(value) => to_send_to_eval += &*value.to_string(),
-                        Value::Float(value) => to_send_to_eval += &*value.to_string(),
-                        _ => unimplemented!()
-                    }
+    pub fn set_arguments(&mut self, arguments: Vec<String>) {
+        self.context.set_arguments(arguments);
+    }
+    pub fn set_overflow(&mut self, overflow: Overflow) {
+        self.overflow = overflow;
+    }
+    pub fn set_call_limit(&mut self, call_limit: usize) {
+        self.call_limit = call_limit;
+    }
+    pub fn context(&mut self) -> &mut Context {
+        &mut self.context
+    }
+    pub fn add_native(&mut self, name: String, native: NativeFunction) {
+        self.natives.insert(name, native);
+    }
+    fn evaluate(&mut self, expression: &Expr) -> Result<Value, Diagnostic> {
+        // calls come back through here once per level of recursion, so the bigger arms
+        // are their own functions to keep this frame small
+        Ok(match expression {
+            Expr::String(value, _) => Value::String(value.clone()),
+            Expr::Float(value, _) => Value::Float(*value),
+            Expr::Integer(value, _) => Value::Int(*value),
+            Expr::BigInteger(value, _) => Value::BigInt(value.clone()),
+            Expr::Char(value, _) => Value::Char(*value),
+            Expr::Bool(value, _) => Value::Bool(*value),
+            Expr::Variable(name) => {
+                match self.defined_variable.get(&name.value) {
+                    Some(referred_variable) => referred_variable.value.clone(),
+                    None => return Err(runtime_error(format!("variable '{}' does not exists", name.value), name))
+                }
+            },
+            Expr::Call(func_name, func_args) => {
+                match self.call_function(func_name, func_args)? {
+                    Some(value) => value,
+                    None => return Err(runtime_error(format!("function '{}' did not return a value", func_name.value), func_name))
+                }
+            },
+            Expr::MethodCall(receiver, method, func_args) => {
+                match self.call_method(receiver, method, func_args)? {
+                    Some(value) => value,
+                    None => return Err(runtime_error(format!("method '{}' did not return a value", method.value), method))
+                }
+            },
+            // the right side is only evaluated when the left side doesn't decide the result
+            Expr::And(left, right, _) => {
+                if self.evaluate(left)? == Value::Bool(false) {
+                    Value::Bool(false)
                 } else {
-                    unimplemented!()
+                    self.evaluate(right)?
                 }
-            } else {
-                unimplemented!("{:?}", current_token)
-            }
-        }
-        eval_string(to_send_to_eval).true_value()
+            },
+            Expr::Or(left, right, _) => {
+                if self.evaluate(left)? == Value::Bool(true) {
+                    Value::Bool(true)
+                } else {
+                    self.evaluate(right)?
+                }
+            },
+            Expr::Interpolation(..) | Expr::Array(..) | Expr::Index(..) | Expr::Field(..) => self.evaluate_compound(expression)?,
+            Expr::Negative(..) | Expr::Positive(..) | Expr::Not(..) | Expr::Cast(..) => self.evaluate_unary(expression)?,
+            Expr::Addition(left, right, _) | Expr::Subtraction(left, right, _) | Expr::Multiply(left, right, _)
+                | Expr::Division(left, right, _) | Expr::Modulo(left, right, _) | Expr::Power(left, right, _)
+                | Expr::Comparison(left, right, _) => self.evaluate_binary(expression, left, right)?
+        })
     }
-    fn var_assign_template(&mut self, variable_name: Token, variable_type: VarTypes, variable_value: Vec<Token>) -> (String, u32){
-        match variable_type {
-            VarTypes::Str => {
-                let ret_eval = self.evaluate(variable_value);
-                self.defined_variable.insert(variable_name.value.clone(), Variable::new(variable_name.value.clone(), Value::String(substring(ret_eval.clone(), 1, (ret_eval.len() - 1) as i32).unwrap()), variable_type));
-                (variable_name.value.clone(), 1)
+    fn evaluate_compound(&mut self, expression: &Expr) -> Result<Value, Diagnostic> {
+        Ok(match expression {
+            Expr::Interpolation(parts, _) => {
+                // written like `out` writes them
+                let mut text = "".to_string();
+                for part in parts {
+                    text += &self.evaluate(part)?.to_string();
+                }
+                Value::String(text)
             },
-            VarTypes::Int => {
-                let ret_eval = self.evaluate(variable_value).parse::<i128>().unwrap();
-                self.defined_variable.insert(variable_name.value.clone(), Variable::new(variable_name.value.clone(), Value::Int(ret_eval), variable_type));
-                (variable_name.value.clone(), 1)
+            Expr::Array(elements, _) => {
+                let mut values = vec![];
+                for element in elements {
+                    values.push(self.evaluate(element)?);
+                }
+                Value::Array(values)
             },
-            VarTypes::Float => {
-                let ret_eval = self.evaluate(variable_value).parse::<f64>().unwrap();
-                self.defined_variable.insert(variable_name.value.clone(), Variable::new(variable_name.value.clone(), Value::Float(ret_eval), variable_type));
-                (variable_name.value.clone(), 1)
+            Expr::Index(..) | Expr::Field(..) => self.read_element(expression)?,
+            _ => unreachable!("not a compound expression")
+        })
+    }
+    fn evaluate_binary(&mut self, expression: &Expr, left: &Expr, right: &Expr) -> Result<Value, Diagnostic> {
+        let left = self.evaluate(left)?;
+        let right = self.evaluate(right)?;
+        binary(expression, left, right, self.overflow)
+    }
+    fn evaluate_unary(&mut self, expression: &Expr) -> Result<Value, Diagnostic> {
+        Ok(match expression {
+            Expr::Negative(operand, operator) => {
+                match self.evaluate(operand)? {
+                    // the smallest int has no positive counterpart
+                    Value::Int(value) => match int_arithmetic(0, value, self.overflow, NEGATE, None) {
+                        Some(negated) => Value::Int(negated),
+                        None => return Err(overflow_error(format!("-({})", value), operator, None))
+                    },
+                    Value::Sized(value, int_type) => match int_arithmetic(0, value, self.overflow, NEGATE, Some(int_type)) {
+                        Some(negated) => Value::Sized(negated, int_type),
+                        None => return Err(overflow_error(format!("-({})", value), operator, Some(int_type)))
+                    },
+                    Value::BigInt(value) => Value::BigInt(-&value),
+                    Value::Float(value) => Value::Float(-value),
+                    value => return Err(runtime_error(format!("'-' can't be used on {:?}", value), operator))
+                }
             },
-            VarTypes::Bool => {
-                let ret_eval = self.evaluate(variable_value).parse::<bool>().unwrap();
-                self.defined_variable.insert(variable_name.value.clone(), Variable::new(variable_name.value.clone(), Value::Bool(ret_eval), variable_type));
-                (variable_name.value.clone(), 1)
+            Expr::Positive(operand, operator) => {
+                match self.evaluate(operand)? {
+                    value @ (Value::Int(_) | Value::Sized(..) | Value::BigInt(_) | Value::Float(_)) => value,
+                    value => return Err(runtime_error(format!("'+' can't be used on {:?}", value), operator))
+                }
             },
-            VarTypes::Struct => {
-                unimplemented!()
+            Expr::Not(operand, operator) => {
+                match self.evaluate(operand)? {
+                    Value::Bool(value) => Value::Bool(!value),
+                    value => return Err(runtime_error(format!("'not' can't be used on {:?}", value), operator))
+                }
+            },
+            Expr::Cast(value, cast_type, operator) => {
+                let value = self.evaluate(value)?;
+                cast(value, cast_type, operator)?
             },
+            _ => unreachable!("not a unary expression")
+        })
+    }
+    fn var_assign_template(&mut self, variable_name: &Token, variable_type: &VarTypes, variable_value: &Expr) -> Result<(String, u32), Diagnostic> {
+        let value = coerce(self.evaluate(variable_value)?, variable_type);
+        self.defined_variable.insert(variable_name.value.clone(), Variable::new(variable_name.value.clone(), value, variable_type.clone()));
+        Ok((variable_name.value.clone(), 1))
+    }
+    fn var_reassignment(&mut self, var_name: &Token, var_value: &Expr) -> Result<(), Diagnostic> {
+        if !self.defined_variable.contains_key(&var_name.value) {
+            return Err(runtime_error(format!("variable '{}' does not exists", var_name.value), var_name))
         }
+        let value = self.evaluate(var_value)?;
+        // the variable is changed where it is, it keeps its type
+        let referred_variable = self.defined_variable.get_mut(&var_name.value).expect("checked above");
+        let value = coerce(value, &referred_variable.var_type);
+        *referred_variable.value_mut() = value;
+        Ok(())
     }
-    fn var_reassignment(&mut self, var_name: Token, var_value: Vec<Token>){
-        let mut referred_variable = self.defined_variable.get(&var_name.value).unwrap().clone();
-        let referred_variable_type = referred_variable.var_type.clone();
-        self.var_assign_template(var_name, referred_variable_type, var_value);
+    fn place(&mut self, target: &Expr) -> Result<(Token, ElementPath), Diagnostic> {
+        // the variable an element is in and the way to it, indexes are evaluated from the outside in, `a[i][j]` gives j then i
+        let mut path = vec![];
+        let mut element_of = target;
+        loop {
+            match element_of {
+                Expr::Index(array, index, bracket) => {
+                    path.push((Some(self.evaluate(index)?), bracket.clone()));
+                    element_of = array;
+                },
+                Expr::Field(object, field) => {
+                    path.push((None, field.clone()));
+                    element_of = object;
+                },
+                _ => break
+            }
+        }
+        let Expr::Variable(var_name) = element_of else {
+            panic!("huh? what? Expected the parser to only allow variables")
+        };
+        path.reverse();
+        Ok((var_name.clone(), path))
     }
-    fn var_assignment(&mut self, var_name: Token, var_type: VarTypes, var_value: Vec<Token>) -> (String, u32){
-        self.var_assign_template(var_name, var_type, var_value)
+    fn read_element(&mut self, target: &Expr) -> Result<Value, Diagnostic> {
+        // `a[i].x` only clones what it reads, the array or struct in a variable stays where it is
+        let mut steps = vec![];
+        let mut element_of = target;
+        loop {
+            match element_of {
+                Expr::Index(array, index, bracket) => {
+                    steps.push((Some(&**index), bracket));
+                    element_of = array;
+                },
+                Expr::Field(object, field) => {
+                    steps.push((None, field));
+                    element_of = object;
+                },
+                _ => break
+            }
+        }
+        steps.reverse();
+        // anything but a variable has to be worked out first, like `make()[0]`
+        let owned = match element_of {
+            Expr::Variable(_) => None,
+            value => Some(self.evaluate(value)?)
+        };
+        let mut indexes = vec![];
+        for (index, _) in &steps {
+            indexes.push(match index {
+                Some(index) => Some(self.evaluate(index)?),
+                None => None
+            });
+        }
+        // `s[i]` on a string variable uses the variable's char table
+        if let (None, Expr::Variable(name), [(Some(_), token)]) = (&owned, element_of, steps.as_slice()) {
+            if let Some(Variable { value: Value::String(text), char_starts: starts, .. }) = self.defined_variable.get_mut(&name.value) {
+                let starts = starts.get_or_insert_with(|| char_starts(text));
+                return char_at(text, starts, indexes.swap_remove(0).expect("an index step"), token)
+            }
+        }
+        let mut element = match (&owned, element_of) {
+            (Some(value), _) => value,
+            (None, Expr::Variable(name)) => match self.defined_variable.get(&name.value) {
+                Some(referred_variable) => &referred_variable.value,
+                None => return Err(runtime_error(format!("variable '{}' does not exists", name.value), name))
+            },
+            (None, _) => unreachable!("only variables aren't worked out first")
+        };
+        let last = steps.len() - 1;
+        for (position, ((_, token), index)) in steps.into_iter().zip(indexes).enumerate() {
+            element = match (element, index) {
+                (Value::Array(elements), Some(index)) => {
+                    let index = array_index(index, elements.len(), "an array", token)?;
+                    &elements[index]
+                },
+                // a char is always the end of the path, strings in arrays and structs don't keep a char table
+                (Value::String(text), Some(index)) if position == last => return char_at(text, &char_starts(text), index, token),
+                (Value::Struct(_, fields), None) => match fields.iter().find(|(field_name, _)| *field_name == token.value) {
+                    Some((_, value)) => value,
+                    None => return Err(runtime_error(format!("there is no field '{}'", token.value), token))
+                },
+                (_, Some(_)) => return Err(runtime_error("only arrays and strings can be indexed".to_string(), token)),
+                (_, None) => return Err(runtime_error("only structs have fields".to_string(), token))
+            };
+        }
+        Ok(element.clone())
     }
-    fn built_in_funcs(&mut self, func_name: Token, func_args: Vec<Token>) -> Option<Value>{
-        match &*func_name.value {
-            "out" => {
-                let function_argument = func_args[0 as usize].clone();
-                if function_argument.is_data_type(){
-                    println!("{}", function_argument.value)
-                } else if function_argument.token_type == TokenType::Identifier {
-                    let argument_name = function_argument.value;
-                    if self.defined_variable.contains_key(&argument_name){
-                        let referred_variable = self.defined_variable.get(&argument_name).unwrap();
-                        match &referred_variable.value {
-                            Value::Int(val) => println!("{}", val),
-                            Value::String(val) => println!("{}", val),
-                            Value::Float(val) => println!("{}", val),
-                            Value::Bool(val) => println!("{}", val),
-
-                        }
-                    } else {
-                        unimplemented!("not yet '{}'", func_name.value)
+    fn element(&mut self, var_name: &Token, path: &[(Option<Value>, Token)]) -> Result<&mut Value, Diagnostic> {
+        let Some(referred_variable) = self.defined_variable.get_mut(&var_name.value) else {
+            return Err(runtime_error(format!("variable '{}' does not exists", var_name.value), var_name))
+        };
+        let mut element = referred_variable.value_mut();
+        for (index, token) in path {
+            element = match (element, index) {
+                (Value::Array(elements), Some(index)) => {
+                    let index = array_index(index.clone(), elements.len(), "an array", token)?;
+                    &mut elements[index]
+                },
+                (Value::Struct(_, fields), None) => {
+                    match fields.iter_mut().find(|(field_name, _)| *field_name == token.value) {
+                        Some((_, field_value)) => field_value,
+                        None => return Err(runtime_error(format!("there is no field '{}'", token.value), token))
                     }
-                } else {
-                    panic!("how did you get here?")
+                },
+                (_, Some(_)) => return Err(runtime_error("only arrays can be indexed".to_string(), token)),
+                (_, None) => return Err(runtime_error("only structs have fields".to_string(), token))
+            };
+        }
+        Ok(element)
+    }
+    fn element_reassignment(&mut self, target: &Expr, var_value: &Expr) -> Result<(), Diagnostic> {
+        let (var_name, path) = self.place(target)?;
+        let value = self.evaluate(var_value)?;
+        // the element keeps its declared type, an int literal put into a [u8] becomes a u8
+        let value = match self.element_type(&var_name, &path) {
+            Some(element_type) => coerce(value, &element_type),
+            None => value
+        };
+        *self.element(&var_name, &path)? = value;
+        Ok(())
+    }
+    fn element_type(&self, var_name: &Token, path: &[(Option<Value>, Token)]) -> Option<VarTypes> {
+        // follows the path over the type the variable was declared with, its value can't tell the type of `[]`
+        let mut element_type = self.defined_variable.get(&var_name.value)?.var_type.clone();
+        for (index, token) in path {
+            element_type = match (element_type, index) {
+                (VarTypes::Array(element_type), Some(_)) => *element_type,
+                (VarTypes::Struct(struct_name), None) => {
+                    let fields = &self.defined_struct.get(&struct_name)?.fields;
+                    fields.iter().find(|(field_name, _)| *field_name == token.value)?.1.clone()
+                },
+                _ => return None
+            };
+        }
+        Some(element_type)
+    }
+    fn var_assignment(&mut self, var_name: &Token, var_type: &VarTypes, var_value: &Expr) -> Result<(String, u32), Diagnostic> {
+        self.var_assign_template(var_name, var_type, var_value)
+    }
+    fn call_native(&mut self, native: &NativeFunction, func_name: &Token, func_args: &[Expr]) -> Result<Option<Value>, Diagnostic> {
+        // natives get their arguments as the types they declared, like functions do
+        let mut arguments = vec![];
+        for (index, argument) in func_args.iter().enumerate() {
+            let declared = match native.arguments.last() {
+                Some(ArgTypes::Variadic(element_type)) if index + 1 >= native.arguments.len() => element_type,
+                _ => &native.arguments[index]
+            };
+            let value = self.evaluate(argument)?;
+            arguments.push(match arg_type_to_var_type(declared) {
+                Some(declared) => coerce(value, &declared),
+                None => value
+            });
+        }
+        let value = match (native.function)(&mut self.context, arguments) {
+            Ok(value) => value,
+            // natives don't know where they were called from
+            Err(mut error) if error.span.line == 0 => {
+                error.span = Span::from_token(func_name);
+                return Err(error)
+            },
+            Err(error) => return Err(error)
+        };
+        // the checker trusts the declared return type, so a native can't give back anything else
+        match (value, native.return_type.first()) {
+            (Value::Void, None) => Ok(None),
+            (value, Some(return_type)) => {
+                let value = coerce(value, return_type);
+                if !fits(&value, return_type) {
+                    return Err(runtime_error(format!("'{}' returned {:?} but its return type is '{:?}'", func_name.value, value, return_type), func_name))
                 }
+                Ok(Some(value))
+            },
+            (value, None) => Err(runtime_error(format!("'{}' returned {:?} but has no return type", func_name.value, value), func_name)
+                .with_note("natives without a return type have to give back `Value::Void`".to_string()))
+        }
+    }
+    fn call_function(&mut self, func_name: &Token, func_args: &[Expr]) -> Result<Option<Value>, Diagnostic> {
+        if let Some(native) = self.natives.get(&func_name.value).cloned() {
+            return self.call_native(&native, func_name, func_args)
+        }
+        let Some(function) = self.defined_function.get(&func_name.value).cloned() else {
+            if let Some(struct_definition) = self.defined_struct.get(&func_name.value) {
+                let fields = struct_definition.fields.clone();
+                return self.construct(func_name, &fields, func_args).map(Some)
             }
-            _ => unimplemented!("not yet '{}'", func_name.value)
+            return Err(runtime_error(format!("function '{}' does not exists", func_name.value), func_name))
+        };
+        Ok(self.run_function(&function, func_name, None, func_args)?.0)
+    }
+    fn method(&self, struct_name: &str, method: &Token) -> Result<Rc<Functions>, Diagnostic> {
+        match self.defined_struct.get(struct_name).and_then(|struct_definition| struct_definition.methods.get(&method.value)) {
+            Some(function) => Ok(function.clone()),
+            None => Err(runtime_error(format!("struct '{}' has no method '{}'", struct_name, method.value), method))
         }
-        None
     }
-    fn func_call(&mut self, func_name: Token, func_args: Vec<Token>){
-        if self.built_in_function.contains(&func_name.value){
-            self.built_in_funcs(func_name, func_args);
-        } else {
-            unimplemented!()
+    fn call_method(&mut self, receiver: &Expr, method: &Token, func_args: &[Expr]) -> Result<Option<Value>, Diagnostic> {
+        // `Point.new()`, a variable named like the struct is called on instead
+        if let Expr::Variable(name) = receiver {
+            if !self.defined_variable.contains_key(&name.value) && self.defined_struct.contains_key(&name.value) {
+                let function = self.method(&name.value, method)?;
+                return Ok(self.run_function(&function, method, None, func_args)?.0)
+            }
+        }
+        // methods change what they are called on when it is a variable or an element of one
+        let place = match receiver.root() {
+            Expr::Variable(_) => Some(self.place(receiver)?),
+            _ => None
+        };
+        let value = match &place {
+            Some((var_name, path)) => self.element(var_name, path)?.clone(),
+            None => self.evaluate(receiver)?
+        };
+        let Value::Struct(struct_name, _) = &value else {
+            return Err(runtime_error("only structs have methods".to_string(), method))
+        };
+        let function = self.method(struct_name, method)?;
+        let (returned, changed) = self.run_function(&function, method, Some(value), func_args)?;
+        if let (Some((var_name, path)), Some(changed)) = (place, changed) {
+            *self.element(&var_name, &path)? = changed;
         }
+        Ok(returned)
     }
-    fn conditions(&mut self, cond: Vec<(Vec<Parsed>, Vec<Token>, (u32, u32))>){
+    fn run_function(&mut self, function: &Functions, func_name: &Token, receiver: Option<Value>, func_args: &[Expr]) -> Result<(Option<Value>, Option<Value>), Diagnostic> {
+        // gives what was returned and `self` once the method is done with it
+        let mut local_variables = HashMap::new();
+        let mut arguments = function.arguments.iter();
+        let self_name = match receiver {
+            Some(receiver) => {
+                let (self_name, self_type) = arguments.next().expect("methods take self first");
+                local_variables.insert(self_name.clone(), Variable::new(self_name.clone(), receiver, self_type.clone()));
+                Some(self_name)
+            },
+            None => None
+        };
+        // arguments are evaluated in the callers scope before switching to the functions own
+        for ((argument_name, argument_type), given) in arguments.zip(func_args) {
+            let value = coerce(self.evaluate(given)?, argument_type);
+            local_variables.insert(argument_name.clone(), Variable::new(argument_name.clone(), value, argument_type.clone()));
+        }
+        // a runaway recursion is stopped before it overflows the rust stack and takes the host down with it
+        if self.call_depth >= self.call_limit {
+            return Err(runtime_error(format!("calls to '{}' went more than {} levels deep", function.name, self.call_limit), func_name)
+                .with_note("a function that keeps calling itself needs a case where it stops".to_string()))
+        }
+        let caller_variables = std::mem::replace(&mut self.defined_variable, local_variables);
+
+        self.call_depth += 1;
+        let mut result = Ok(());
+        for statement in &function.statements {
+            result = self.individuals(statement).map(|_| ());
+            if result.is_err() || self.returning {
+                break
+            }
+        }
+        self.call_depth -= 1;
+
+        // the callers variables come back even after an error, `exit()` keeps them around
+        let mut function_variables = std::mem::replace(&mut self.defined_variable, caller_variables);
+        self.returning = false;
+        let returned = self.return_value.take();
+        result?;
+        let returned = returned.map(|returned| match function.return_type.first() {
+            Some(return_type) => coerce(returned, return_type),
+            None => returned
+        });
+        if !function.return_type.is_empty() && returned.is_none() {
+            return Err(runtime_error(format!("function '{}' ended without returning a value", function.name), func_name))
+        }
+        let changed = self_name.and_then(|self_name| function_variables.remove(self_name)).map(|variable| variable.value);
+        Ok((returned, changed))
+    }
+    fn construct(&mut self, struct_name: &Token, fields: &[(String, VarTypes)], func_args: &[Expr]) -> Result<Value, Diagnostic> {
+        // `Point(1, 2)` gives the fields their values in the order they were defined
+        let mut values = vec![];
+        for ((field_name, field_type), given) in fields.iter().zip(func_args) {
+            values.push((field_name.clone(), coerce(self.evaluate(given)?, field_type)));
+        }
+        Ok(Value::Struct(struct_name.value.clone(), values))
+    }
+    fn func_call(&mut self, func_name: &Token, func_args: &[Expr]) -> Result<(), Diagnostic> {
+        self.call_function(func_name, func_args)?;
+        Ok(())
+    }
+    fn return_from_function(&mut self, values: Option<&Expr>) -> Result<(), Diagnostic> {
+        if let Some(values) = values {
+            self.return_value = Some(self.evaluate(values)?);
+        }
+        self.returning = true;
+        Ok(())
+    }
+    fn conditions(&mut self, cond: &[(Vec<Parsed>, Expr, (u32, u32))]) -> Result<(), Diagnostic> {
         for statement in cond {
-            let return_of_evaluating_condition = self.evaluate(statement.1).parse::<bool>().unwrap();
+            let return_of_evaluating_condition = self.evaluate(&statement.1)? == Value::Bool(true);
             if return_of_evaluating_condition {
-                let mut scope = vec![];
-                for statement in statement.0  {
-                    let ret = self.individuals(statement);
-                    if ret.1 != 0 {
-                       scope.push(ret)
-                    }
-                }
-                for defined in scope {
-                    match defined.1 {
-                        1 => { self.defined_variable.remove(&defined.0).expect("huh?");},
-                        _ => unimplemented!()
-                    }
+                self.block(&statement.0)?;
+                break
+            }
+        }
+        Ok(())
+    }
+    fn block(&mut self, statements: &[Parsed]) -> Result<(), Diagnostic> {
+        let mut scope = vec![];
+        let mut result = Ok(());
+        for statement in statements  {
+            let ret = match self.individuals(statement) {
+                Ok(ret) => ret,
+                Err(error) => {
+                    result = Err(error);
+                    break
                 }
-              break
+            };
+            if ret.1 != 0 {
+               scope.push(ret)
+            }
+            if self.returning || self.breaking || self.continuing {
+                break
+            }
+        }
+        // removed after an error too, so they don't outlive the block when `exit()` keeps the rest
+        for defined in scope {
+            match defined.1 {
+                1 => { self.defined_variable.remove(&defined.0).expect("huh?");},
+                _ => unimplemented!()
             }
         }
+        result
+    }
+    fn while_loop(&mut self, condition: &Expr, statements: &[Parsed]) -> Result<(), Diagnostic> {
+        while self.evaluate(condition)? == Value::Bool(true) {
+            self.block(statements)?;
+            self.continuing = false;
+            if self.breaking {
+                self.breaking = false;
+                break
+            }
+            if self.returning {
+                break
+            }
+        }
+        Ok(())
+    }
+    fn for_loop(&mut self, variable: &Token, start: &Expr, end: &Expr, step: Option<&Expr>, statements: &[Parsed]) -> Result<(), Diagnostic> {
+        // the bounds are evaluated once, before the first run of the body
+        let (Value::Int(start), Value::Int(end)) = (self.evaluate(start)?, self.evaluate(end)?) else {
+            return Err(runtime_error("Expected the range to be 'Int'".to_string(), variable))
+        };
+        let step = match step {
+            None => 1,
+            Some(step) => match self.evaluate(step)? {
+                Value::Int(step_value) if step_value > 0 => step_value,
+                Value::Int(step_value) => return Err(runtime_error(format!("step must be positive got {}", step_value), step.token())),
+                _ => return Err(runtime_error("Expected the step to be 'Int'".to_string(), step.token()))
+            }
+        };
 
+        let mut current = start;
+        while current < end {
+            self.defined_variable.insert(variable.value.clone(), Variable::new(variable.value.clone(), Value::Int(current), VarTypes::Int));
+            let result = self.block(statements);
+            self.defined_variable.remove(&variable.value);
+            result?;
+            self.continuing = false;
+            if self.breaking {
+                self.breaking = false;
+                break
+            }
+            if self.returning {
+                break
+            }
+            current = match current.checked_add(step) {
+                Some(next) => next,
+                None => break
+            };
+        }
+        Ok(())
     }
-    fn individuals(&mut self, part: Parsed) -> (String, u32){
+    fn individuals(&mut self, part: &Parsed) -> Result<(String, u32), Diagnostic> {
         match part {
                 Parsed::VariableAssignment(var_name, var_type, var_value) => {
-                    let (variable_name, variable_type) = self.var_assignment(var_name.clone(), var_type.clone(), var_value.clone());
-                    (variable_name, variable_type)
+                    return self.var_assignment(var_name, var_type, var_value)
                 },
-                Parsed::FuncCall(func_name, func_args) => {
-                    self.func_call(func_name, func_args);
-                    ("".to_string(), 0)
-                },
-                Parsed::VariableReassignment(var_name, var_value) => {
-                    self.var_reassignment(var_name.clone(), var_value.clone());
-                    ("".to_string(), 0)
-                },
-                Parsed::Conditions(cond) => {
-                    self.conditions(cond);
-                    ("".to_string(), 0)
+                Parsed::FuncCall(func_name, func_args) => self.func_call(func_name, func_args)?,
+                Parsed::VariableReassignment(var_name, var_value) => self.var_reassignment(var_name, var_value)?,
+                Parsed::ElementReassignment(target, var_value) => self.element_reassignment(target, var_value)?,
+                Parsed::MethodCall(call) => {
+                    self.evaluate_expression(call)?;
                 },
+                Parsed::Conditions(cond) => self.conditions(cond)?,
+                // registered before the program runs
+                Parsed::FunctionDefinition(..) | Parsed::StructDefinition(..) | Parsed::Impl(..) => {},
+                Parsed::Return(_, values) => self.return_from_function(values.as_ref())?,
+                Parsed::While(condition, body, _) => self.while_loop(condition, body)?,
+                Parsed::For(variable, start, end, step, body) => self.for_loop(variable, start, end, step.as_ref(), body)?,
+                Parsed::Break(_) => self.breaking = true,
+                Parsed::Continue(_) => self.continuing = true,
                 _ => unimplemented!()
             }
+        Ok(("".to_string(), 0))
+    }
+    pub fn variables(&self) -> Vec<(String, VarTypes, Value)> {
+        let mut variables: Vec<(String, VarTypes, Value)> = self.defined_variable.values()
+            .map(|variable| (variable.name.clone(), variable.var_type.clone(), variable.value.clone()))
+            .collect();
+        variables.sort_by(|left, right| left.0.cmp(&right.0));
+        variables
+    }
+    pub fn variable(&self, name: &str) -> Option<Value> {
+        self.defined_variable.get(name).map(|variable| variable.value.clone())
     }
-    pub fn run(&mut self){
-        let program = self.program.clone();
-        for part in program {
-            self.individuals(part);
+    pub fn set_variable(&mut self, name: String, value: Value, var_type: VarTypes) {
+        self.defined_variable.insert(name.clone(), Variable::new(name, value, var_type));
+    }
+    pub fn evaluate_expression(&mut self, expression: &Expr) -> Result<Option<Value>, Diagnostic> {
+        // calls to functions that return nothing give None instead of an error
+        match expression {
+            Expr::Call(func_name, func_args) => self.call_function(func_name, func_args),
+            Expr::MethodCall(receiver, method, func_args) => self.call_method(receiver, method, func_args),
+            expression => self.evaluate(expression).map(Some)
         }
     }
+    pub fn run(&mut self) -> Result<(), Diagnostic> {
+        let program = std::mem::take(&mut self.program);
+        self.run_statements(program)
+    }
+    pub fn run_statements(&mut self, program: Vec<Parsed>) -> Result<(), Diagnostic> {
+        // structs go first so impl blocks before them can add their methods
+        for part in &program {
+            if let Parsed::StructDefinition(struct_name, fields) = part {
+                let fields = fields.iter().map(|(field_name, field_type)| (field_name.value.clone(), field_type.clone())).collect();
+                self.defined_struct.insert(struct_name.value.clone(), Structs { fields, methods: HashMap::new() });
+            }
+        }
+        for part in &program {
+            match part {
+                Parsed::FunctionDefinition(func_name, ..) => {
+                    self.defined_function.insert(func_name.value.clone(), function_definition(part));
+                },
+                Parsed::Impl(struct_name, functions) => {
+                    let Some(struct_definition) = self.defined_struct.get_mut(&struct_name.value) else {
+                        panic!("huh? what? Expected the checker to only allow impl blocks of structs")
+                    };
+                    for function in functions {
+                        let function = function_definition(function);
+                        struct_definition.methods.insert(function.name.clone(), function);
+                    }
+                },
+                _ => {}
+            }
+        }
+        for part in &program {
+            self.individuals(part)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Math;
+    use crate::lexer::{Lexer, TokenType};
+
+    fn single_test(expression: &str, expected: &str) {
+        let mut tokens = Lexer::new().lex_text(expression.to_string()).unwrap_or_else(|error| panic!("{}", error));
+        tokens.retain(|token| token.token_type != TokenType::EndOfFile);
+        let parsed = Math::new().parse(tokens).unwrap_or_else(|error| panic!("{}", error));
+        let mut interpreter = Interpreter::new(Parsed::Program(vec![]), Natives::standard());
+        let evaluation = interpreter.evaluate(&parsed).unwrap_or_else(|error| panic!("{}", error));
+        assert_eq!(evaluation.to_string(), expected, "{}", expression);
+    }
+
+    #[test]
+    fn numbers() {
+        single_test("123", "123");
+        single_test("1 + 1", "2");
+        single_test("43 * 0 + 43", "43");
+        single_test("1/1", "1");
+        single_test("2.0 + 2.3", "4.3");
+        single_test("7 / 2", "3");
+        single_test("-7 % 3", "-1");
+    }
+
+    #[test]
+    fn comparisons() {
+        single_test("123 == 123", "true");
+        single_test("123 > 120", "true");
+        single_test("123 < 120", "false");
+        single_test("123 != 120", "true");
+        single_test("123 >= 120", "true");
+        single_test("123 <= 120", "false");
+    }
+
+    #[test]
+    fn strings() {
+        single_test(r#""ab" + "cd""#, "abcd");
+        single_test(r#""ab" == "ab""#, "true");
+    }
 }
@@ -1,5 +1,4 @@
-use std::collections::HashMap;
-use std::ops::Deref;
+use crate::error::{Diagnostic, ErrorKind, Span};
 
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub enum TokenType {
@@ -18,6 +17,7 @@ pub enum TokenType {
     AssignmentOperator,
     DirectMemberSelection,
     ComparisonOperation,
+    Range,
 
 
     // keywords
@@ -25,9 +25,19 @@ pub enum TokenType {
     Return,
     Fun,
     And,
+    Not,
     If,
     Else,
     Or,
+    While,
+    Break,
+    Continue,
+    For,
+    In,
+    Step,
+    Struct,
+    Impl,
+    As,
 
     // symbols
     EndLine,
@@ -44,12 +54,23 @@ pub enum TokenType {
     NullForParser
 }
 
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Segment {
+    Text(String),
+    // the tokens of an expression in braces, `{count * 2}`
+    Code(Vec<Token>),
+}
+
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Token {
     pub token_type: TokenType,
     pub value: String,
     pub x: u32,
     pub y: u32,
+    // characters the token takes up in the source
+    pub length: u32,
+    // only strings with expressions in them have segments, their value is what was written between the quotes
+    pub segments: Vec<Segment>,
 }
 
 impl Token {
@@ -58,7 +79,9 @@ impl Token {
             token_type,
             value,
             x: 0,
-            y: 0
+            y: 0,
+            length: 0,
+            segments: vec![]
         }
     }
     pub fn set_xy(&mut self, x: u32, y: u32){
@@ -77,12 +100,17 @@ impl Token {
     pub fn is_bool(&self) -> bool {
         self.token_type == TokenType::Boolean
     }
+    pub fn is_char(&self) -> bool {
+        self.token_type == TokenType::Character
+    }
     pub fn is_data_type(&self) -> bool {
-        self.is_float() || self.is_bool() || self.is_string() || self.is_integer()
+        self.is_float() || self.is_bool() || self.is_string() || self.is_integer() || self.is_char()
     }
     pub fn true_value(&self) -> String{
         if self.is_string(){
-            format!("\"{}\"", self.value)
+            format!("{:?}", self.value)
+        } else if self.is_char() {
+            format!("{:?}", self.value.chars().next().unwrap_or_default())
         } else {
             self.value.clone()
         }
@@ -98,8 +126,7 @@ pub struct Lexer {
     y: i32,
     tok_start_x: i32,
     tok_start_y: i32,
-    log: Vec<String>,
-    error: Vec<String>,
+    tok_start_index: i32,
     current_tokens: Vec<Token>
 }
 
@@ -114,14 +141,22 @@ impl Lexer {
             y: 1,
             tok_start_x: 0,
             tok_start_y: 0,
-            log: vec![],
-            error: vec![],
+            tok_start_index: 0,
             current_tokens: vec![]
         }
     }
     pub fn pos_starter(&mut self){
         self.tok_start_x = self.x;
         self.tok_start_y = self.y;
+        self.tok_start_index = self.index;
+    }
+    fn error(&self, message: String) -> Diagnostic {
+        Diagnostic::new(ErrorKind::Syntax, message, Span::new(self.tok_start_y as u32, self.tok_start_x as u32, 1))
+    }
+    // a token started while another one was still being read, the lexer has a bug if this is ever seen
+    fn lost_track(&self) -> Diagnostic {
+        self.error("the lexer lost track of the token before this".to_string())
+            .with_note("this is a bug in the lexer, not in the script".to_string())
     }
     pub fn next_char(&mut self) -> bool {
         self.index += 1;
@@ -140,17 +175,14 @@ impl Lexer {
         }
     }
     pub fn get_next_char_ignore_space(&self) -> Option<char> {
-        let mut ind = self.index as usize;
-        ind += 1;
-        let mut character = self.text_to_lex[ind];
-        while character == ' ' {
+        let mut ind = self.index as usize + 1;
+        while self.text_to_lex.get(ind) == Some(&' ') {
             ind += 1;
-            character = self.text_to_lex[ind];
         }
-        Some(character)
+        self.text_to_lex.get(ind).copied()
     }
     pub fn get_char(&self, ahead: i32) -> Option<char> {
-        Some(self.text_to_lex[(self.index + ahead) as usize])
+        self.text_to_lex.get((self.index + ahead) as usize).copied()
     }
      pub fn get_next_char(&self) -> Option<char> {
         self.get_char(1)
@@ -158,8 +190,16 @@ impl Lexer {
     pub fn add_base(&mut self, tok_type: TokenType, value: String){
         let mut tok = Token::new(tok_type, value);
         tok.set_xy(self.tok_start_x as u32, self.tok_start_y as u32);
+        // the lexer sits on the last character of the token when it gets added
+        tok.length = (self.index - self.tok_start_index + 1) as u32;
         self.current_tokens.push(tok);
     }
+    fn set_last_length(&mut self, length: usize){
+        // numbers and words are added once the lexer already moved past them
+        if let Some(tok) = self.current_tokens.last_mut() {
+            tok.length = length as u32;
+        }
+    }
     pub fn add_special(&mut self, tok_type: TokenType ){
         self.add_base(tok_type, "".to_string());
     }
@@ -168,17 +208,171 @@ impl Lexer {
     }
     pub fn add_string(&mut self, value: String){
         self.add_base(TokenType::String, value);
+        // a string over several lines is underlined to the end of its first line
+        let written = &self.text_to_lex[self.tok_start_index as usize..=self.index as usize];
+        if let Some(newline) = written.iter().position(|character| *character == '\n') {
+            self.set_last_length(newline);
+        }
+    }
+    fn add_interpolated_string(&mut self, segments: Vec<Segment>){
+        let written = self.text_to_lex[self.tok_start_index as usize + 1..self.index as usize].iter().collect();
+        self.add_string(written);
+        if let Some(tok) = self.current_tokens.last_mut() {
+            tok.segments = segments;
+        }
+    }
+    fn interpolation(&mut self) -> Result<Vec<Token>, Diagnostic> {
+        // the lexer is on the opening brace, it is left on the closing one
+        let (x, y, start) = (self.x, self.y, self.index);
+        let brace_error = |message: String| Diagnostic::new(ErrorKind::Syntax, message, Span::new(y as u32, x as u32, 1));
+        let mut depth = 0;
+        let mut in_string = false;
+        let mut in_char = false;
+        loop {
+            if !self.next_char() {
+                return Err(brace_error("unclosed '{' in string".to_string())
+                    .with_note("braces in strings are written `\\{` and `\\}`".to_string()))
+            }
+            match self.current_char {
+                '\\' if in_string || in_char => { self.next_char(); },
+                '"' if !in_char => in_string = !in_string,
+                '\'' if !in_string => in_char = !in_char,
+                _ if in_string || in_char => {},
+                '{' => depth += 1,
+                '}' if depth == 0 => break,
+                '}' => depth -= 1,
+                _ => {}
+            }
+        }
+        let code: String = self.text_to_lex[start as usize + 1..self.index as usize].iter().collect();
+        if code.trim().is_empty() {
+            return Err(brace_error("Expected an expression between '{}' in string".to_string())
+                .with_note("braces in strings are written `\\{` and `\\}`".to_string()))
+        }
+        // lexed on its own, starting where it is in the source so errors point at it
+        let mut code_lexer = Lexer::new();
+        code_lexer.x = x;
+        code_lexer.y = y;
+        let mut tokens = code_lexer.lex_text(code)?;
+        tokens.pop();
+        Ok(tokens)
+    }
+    fn escape(&mut self) -> Result<char, Diagnostic> {
+        // the lexer is on the backslash, it is left on the last character of the escape
+        let (x, y, start) = (self.x as u32, self.y as u32, self.index);
+        if !self.next_char() {
+            return Err(self.error("unclosed string".to_string()))
+        }
+        let escape_error = |message: String, end: i32| Diagnostic::new(ErrorKind::Syntax, message, Span::new(y, x, (end - start + 1) as u32))
+            .with_note("the escapes are \\n \\t \\r \\0 \\\\ \\\" \\' \\{ \\} and \\u{...}".to_string());
+        Ok(match self.current_char {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            '{' => '{',
+            '}' => '}',
+            'u' => {
+                // `\u{1F600}`, up to 6 hex digits
+                if self.get_next_char() != Some('{') {
+                    return Err(escape_error("Expected '{' after '\\u'".to_string(), self.index))
+                }
+                self.next_char();
+                let mut digits = "".to_string();
+                while self.get_next_char().is_some_and(|character| character != '}' && character != '"' && character != '\n') {
+                    self.next_char();
+                    digits.push(self.current_char);
+                }
+                if self.get_next_char() != Some('}') {
+                    return Err(escape_error("unclosed unicode escape".to_string(), self.index))
+                }
+                self.next_char();
+                let code_point = if digits.len() <= 6 { u32::from_str_radix(&digits, 16).ok() } else { None };
+                match code_point.and_then(char::from_u32) {
+                    Some(character) => character,
+                    None => return Err(escape_error(format!("'{}' is not a unicode character", digits), self.index))
+                }
+            },
+            other => return Err(escape_error(format!("unknown escape '\\{}'", other), self.index))
+        })
+    }
+    fn character(&mut self) -> Result<(), Diagnostic> {
+        // `'a'` or `'\n'`, the lexer is on the opening quote and is left on the closing one
+        if !self.next_char() || self.current_char == '\n' {
+            return Err(self.error("unclosed char".to_string()))
+        }
+        let value = match self.current_char {
+            '\'' => return Err(self.error("empty char".to_string())
+                .with_note("a char holds one character, `' '` is a space".to_string())),
+            '\\' => self.escape()?,
+            character => character
+        };
+        if self.get_next_char() != Some('\'') {
+            // `'ab'` is most likely meant to be a string
+            let rest_of_line = self.text_to_lex[self.index as usize + 1..].iter().take_while(|character| **character != '\n');
+            if rest_of_line.clone().any(|character| *character == '\'') {
+                let written: String = rest_of_line.take_while(|character| **character != '\'').collect();
+                return Err(self.error(format!("'{}{}' is more than one character", value, written))
+                    .with_note("strings are written with double quotes".to_string()))
+            }
+            return Err(self.error("unclosed char".to_string()))
+        }
+        self.next_char();
+        self.add_base(TokenType::Character, value.to_string());
+        Ok(())
     }
     pub fn add_integer(&mut self, value: String){
+        let length = value.chars().count();
         self.add_base(TokenType::Integer, value);
+        self.set_last_length(length);
     }
     pub fn add_float(&mut self, value: String){
+        let length = value.chars().count();
         self.add_base(TokenType::FloatingPoint, value);
+        self.set_last_length(length);
+    }
+    // the number or word that was being read
+    fn add_pending(&mut self, value: String, word: bool, float: bool) {
+        match (word, float) {
+            (true, _) => self.add_word(value),
+            (false, true) => self.add_float(value),
+            (false, false) => self.add_integer(value)
+        }
     }
     pub fn add_identifier(&mut self, value: String){
         self.add_base(TokenType::Identifier, value);
     }
-    fn lex(&mut self) -> Vec<Token> {
+    pub fn add_word(&mut self, value: String){
+        // keywords get their own token, everything else is an identifier
+        let length = value.chars().count();
+        match &*value {
+            "and" => self.add_special(TokenType::And),
+            "or" => self.add_special(TokenType::Or),
+            "not" => self.add_special(TokenType::Not),
+            "import" =>  self.add_special(TokenType::Import),
+            "return" => self.add_special(TokenType::Return),
+            "if" => self.add_special(TokenType::If),
+            "else" => self.add_special(TokenType::Else),
+            "fun" => self.add_special(TokenType::Fun),
+            "while" => self.add_special(TokenType::While),
+            "break" => self.add_special(TokenType::Break),
+            "continue" => self.add_special(TokenType::Continue),
+            "for" => self.add_special(TokenType::For),
+            "in" => self.add_special(TokenType::In),
+            "step" => self.add_special(TokenType::Step),
+            "struct" => self.add_special(TokenType::Struct),
+            "impl" => self.add_special(TokenType::Impl),
+            "as" => self.add_special(TokenType::As),
+            "true" => self.add_special_bare(TokenType::Boolean, "true".to_string()),
+            "false" => self.add_special_bare(TokenType::Boolean, "false".to_string()),
+            _ => {self.add_identifier(value)}
+        }
+        self.set_last_length(length);
+    }
+    fn lex(&mut self) -> Result<Vec<Token>, Diagnostic> {
         /*
         hierarchy
 
@@ -196,11 +390,15 @@ impl Lexer {
         let mut unknown_length = "".to_string();
         let mut unknown_length_being_used= false;
         let mut str_on = false;
+        // raw strings have no escapes or expressions
+        let mut raw_on = false;
+        // the parts of a string before the expressions in it
+        let mut segments = vec![];
         let mut comment_on = false;
         let mut id_on = false;
         let mut num_on = false;
 
-        let mut int = false;
+        // numbers are ints until they get a `.`
         let mut float = false;
 
 
@@ -213,29 +411,47 @@ impl Lexer {
             if self.current_char == '"' && !comment_on {
                 if str_on {
                     str_on = false;
+                    raw_on = false;
                     unknown_length_being_used = false;
-                    self.add_string(unknown_length.clone());
+                    if segments.is_empty() {
+                        self.add_string(unknown_length.clone());
+                    } else {
+                        segments.push(Segment::Text(unknown_length.clone()));
+                        self.add_interpolated_string(std::mem::take(&mut segments));
+                    }
                     unknown_length = "".to_string();
                 } else {
+                    // a number or word right before the quote ends there, `1"a"` is two tokens
+                    if num_on || id_on {
+                        self.add_pending(std::mem::take(&mut unknown_length), id_on, float);
+                        (num_on, id_on, float) = (false, false, false);
+                        unknown_length_being_used = false;
+                    }
                     self.pos_starter();
                     str_on = true;
 
                     if unknown_length_being_used {
-                        panic!("shit something went wrong! at line {} char {}", self.y, self.tok_start_y);
+                        return Err(self.lost_track())
                     }
 
                     unknown_length_being_used = true;
                 }
 
+            } else if str_on && !raw_on && self.current_char == '\\' {
+                let escaped = self.escape()?;
+                unknown_length.push(escaped);
+
+            } else if str_on && !raw_on && self.current_char == '{' {
+                let code = self.interpolation()?;
+                segments.push(Segment::Text(std::mem::take(&mut unknown_length)));
+                segments.push(Segment::Code(code));
+
             } else if str_on || comment_on {
-                if self.current_char == '\n' {
-                    if str_on {
-                        panic!("unclosed string at line {} char {}", self.tok_start_y, self.tok_start_x);
-                    } else {
-                        comment_on = false;
-                        unknown_length_being_used = false;
-                        unknown_length = "".to_string();
-                    }
+                // strings can go over several lines, comments end with theirs
+                if self.current_char == '\n' && comment_on {
+                    comment_on = false;
+                    unknown_length_being_used = false;
+                    unknown_length = "".to_string();
                 } else {
                     unknown_length += &self.current_char.to_string();
                 }
@@ -247,35 +463,34 @@ impl Lexer {
                     unknown_length += &self.current_char.to_string();
                     self.pos_starter();
                     num_on = true;
-                    int = true;
 
                     if unknown_length_being_used {
-                        panic!("shit something went wrong! at line {} char {}", self.y, self.tok_start_y);
+                        return Err(self.lost_track())
                     }
 
                     unknown_length_being_used = true;
                 }
-            } else if self.current_char == '.' && num_on && num.contains(&self.get_next_char_ignore_space().expect(&format!("expected char! at line {} char {}", self.y, self.tok_start_y))){
-                int = false;
+            } else if self.current_char == '.' && num_on && num.contains(&self.get_next_char_ignore_space().unwrap_or(' ')){
                 float = true;
                 unknown_length += ".";
 
             } else if allowed_for_id.contains(&self.current_char){
 
-                // to avoid errors
+                // a word right after a number starts a new token, `1a` is two
                 if num_on {
-                    if int {
-                        self.add_integer(unknown_length.clone());
-                        int = false;
-                    } else if float {
-                        self.add_float(unknown_length.clone());
-                        float = false;
-                    } else {
-                        panic!("what? how?! at line {} char {}", self.y, self.tok_start_y);
-                    }
-                    num_on = false;
+                    self.add_pending(std::mem::take(&mut unknown_length), false, float);
+                    (num_on, float) = (false, false);
                     unknown_length_being_used = false;
-                    unknown_length = "".to_string();
+                }
+
+                if !id_on && self.current_char == 'r' && self.get_next_char() == Some('"') {
+                    // raw strings keep backslashes as they are, `r"C:\new"`
+                    self.pos_starter();
+                    self.next_char();
+                    str_on = true;
+                    raw_on = true;
+                    unknown_length_being_used = true;
+                    continue
                 }
 
                 if id_on {
@@ -286,71 +501,46 @@ impl Lexer {
                     id_on = true;
 
                     if unknown_length_being_used {
-                        panic!("shit something went wrong! at line {} char {}", self.y, self.tok_start_y);
+                        return Err(self.lost_track())
                     }
 
                     unknown_length_being_used = true;
                 }
             } else {
-                if num_on {
-                    if int {
-                        self.add_integer(unknown_length.clone());
-                        int = false;
-                    } else if float {
-                        self.add_float(unknown_length.clone());
-                        float = false;
-                    } else {
-                        panic!("what? how?! at line {} char {}", self.tok_start_y, self.tok_start_x);
-                    }
-                    num_on = false;
+                if num_on || id_on {
+                    self.add_pending(std::mem::take(&mut unknown_length), id_on, float);
+                    (num_on, id_on, float) = (false, false, false);
                     unknown_length_being_used = false;
-                    unknown_length = "".to_string();
-                } else if id_on {
-                    match &*unknown_length {
-                        "and" => self.add_special(TokenType::And),
-                        "or" => self.add_special(TokenType::Or),
-                        "import" =>  self.add_special(TokenType::Import),
-                        "return" => self.add_special(TokenType::Return),
-                        "if" => self.add_special(TokenType::If),
-                        "else" => self.add_special(TokenType::Else),
-                        "fun" => self.add_special(TokenType::Fun),
-                        "true" => self.add_special_bare(TokenType::Boolean, "true".to_string()),
-                        "false" => self.add_special_bare(TokenType::Boolean, "false".to_string()),
-                        _ => {self.add_identifier(unknown_length.clone())}
-                    }
-                    id_on = false;
-                    unknown_length_being_used = false;
-                    unknown_length = "".to_string();
                 }
 
                 self.pos_starter();
                 match self.current_char {
                     '=' =>
                         {
-                            let next = self.get_next_char();
-                            if !next.is_some() {
-                                panic!("Expected Continuation at line {} char {}", self.tok_start_y, self.tok_start_x);
-                            }
-                            let next_char = next.unwrap();
+                            let next_char = match self.get_next_char() {
+                                Some(next_char) => next_char,
+                                None => return Err(self.error("Expected Continuation".to_string()))
+                            };
                             if next_char == '=' {
-                                self.add_special_bare(TokenType::ComparisonOperation, "==".to_string());
                                 self.next_char();
+                                self.add_special_bare(TokenType::ComparisonOperation, "==".to_string());
                             } else {
-                               panic!("Please use assignment arrow '<-',  at line {} char {}", self.tok_start_y, self.tok_start_x);
+                               return Err(self.error("Unexpected '='".to_string())
+                                   .with_note("assignments use the assignment arrow '<-'".to_string()))
                             }
                         },
                     '!' =>
                         {
-                            let next = self.get_next_char();
-                            if !next.is_some() {
-                                panic!("Expected Continuation at line {} char {}", self.tok_start_y, self.tok_start_x);
-                            }
-                            let next_char = next.unwrap();
+                            let next_char = match self.get_next_char() {
+                                Some(next_char) => next_char,
+                                None => return Err(self.error("Expected Continuation".to_string()))
+                            };
                             if next_char == '=' {
-                                self.add_special_bare(TokenType::ComparisonOperation, "!=".to_string());
                                 self.next_char();
+                                self.add_special_bare(TokenType::ComparisonOperation, "!=".to_string());
                             } else {
-                                panic!("Unexpected '{}',  at line {} char {}", self.current_char, self.tok_start_y, self.tok_start_x);
+                                return Err(self.error(format!("Unexpected '{}'", self.current_char))
+                                    .with_note("'!' is only used in '!=', negation is written with 'not'".to_string()))
                             }
                         },
                     '%' => self.add_special_bare(TokenType::MathOperation, "%".to_string()),
@@ -358,17 +548,16 @@ impl Lexer {
                     '-' => self.add_special_bare(TokenType::MathOperation, "-".to_string()),
                     '/' =>
                         {
-                            let next = self.get_next_char();
-                            if !next.is_some() {
-                                panic!("Expected Continuation at line {} char {}", self.tok_start_y, self.tok_start_x);
-                            }
-                            let next_char = next.unwrap();
+                            let next_char = match self.get_next_char() {
+                                Some(next_char) => next_char,
+                                None => return Err(self.error("Expected Continuation".to_string()))
+                            };
                             if next_char == '/' {
                                 self.pos_starter();
                                 comment_on = true;
 
                                 if unknown_length_being_used {
-                                    panic!("shit something went wrong! at line {} char {}", self.y, self.tok_start_y);
+                                    return Err(self.lost_track())
                                 }
 
                                 unknown_length_being_used = true;
@@ -376,43 +565,58 @@ impl Lexer {
                                  self.add_special_bare(TokenType::MathOperation, "/".to_string())
                             }
                     },
-                    '*' => self.add_special_bare(TokenType::MathOperation, "*".to_string()),
+                    '*' =>
+                        {
+                            if self.get_next_char() == Some('*') {
+                                self.next_char();
+                                self.add_special_bare(TokenType::MathOperation, "**".to_string());
+                            } else {
+                                self.add_special_bare(TokenType::MathOperation, "*".to_string())
+                            }
+                        },
                     ';' => self.add_special(TokenType::EndLine),
-                    '.' => self.add_special(TokenType::DirectMemberSelection),
+                    '.' =>
+                        {
+                            if self.get_next_char() == Some('.') {
+                                self.next_char();
+                                self.add_special(TokenType::Range);
+                            } else {
+                                self.add_special(TokenType::DirectMemberSelection)
+                            }
+                        },
                     '(' => self.add_special(TokenType::ParenthesisOpen),
                     ')' => self.add_special(TokenType::ParenthesisClose),
                     ',' => self.add_special(TokenType::SeperatorComma),
                     '>' =>
                         {
-                            let next = self.get_next_char();
-                            if !next.is_some() {
-                                panic!("Expected Continuation at line {} char {}", self.tok_start_y, self.tok_start_x);
-                            }
-                            let next_char = next.unwrap();
+                            let next_char = match self.get_next_char() {
+                                Some(next_char) => next_char,
+                                None => return Err(self.error("Expected Continuation".to_string()))
+                            };
                             if next_char == '=' {
-                                self.add_special_bare(TokenType::ComparisonOperation, ">=".to_string());
                                 self.next_char();
+                                self.add_special_bare(TokenType::ComparisonOperation, ">=".to_string());
                             } else {
                                 self.add_special_bare(TokenType::ComparisonOperation, ">".to_string())
                             }
                         },
                     '<' =>
                         {
-                            let next = self.get_next_char();
-                            if !next.is_some() {
-                                panic!("Expected Continuation at line {} char {}", self.tok_start_y, self.tok_start_x);
-                            }
-                            let next_char = next.unwrap();
+                            let next_char = match self.get_next_char() {
+                                Some(next_char) => next_char,
+                                None => return Err(self.error("Expected Continuation".to_string()))
+                            };
                             if next_char == '=' {
-                                self.add_special_bare(TokenType::ComparisonOperation, "<=".to_string());
                                 self.next_char();
+                                self.add_special_bare(TokenType::ComparisonOperation, "<=".to_string());
                             } else if next_char == '-' {
-                                self.add_special(TokenType::AssignmentArrow);
                                 self.next_char();
+                                self.add_special(TokenType::AssignmentArrow);
                             } else {
                                 self.add_special_bare(TokenType::ComparisonOperation, "<".to_string())
                             }
                         },
+                    '\'' => self.character()?,
                     '{' => self.add_special(TokenType::CurlyBracketOpen),
                     '}' => self.add_special(TokenType::CurlyBracketClose),
                     '[' => self.add_special(TokenType::BracketOpen),
@@ -420,159 +624,281 @@ impl Lexer {
                     ' ' => {},
                     '\n' => {},
                     '\t' => {},
-                    _ => {unimplemented!("not added -> {} <-, at line {} char {}", self.current_char, self.tok_start_y, self.tok_start_x)}
+                    _ => return Err(self.error(format!("Unknown character '{}'", self.current_char)))
                 }
             }
         }
-        if num_on {
-            if int {
-                self.add_integer(unknown_length.clone());
-            } else if float {
-                self.add_float(unknown_length.clone());
-            } else {
-                panic!("what? how?")
-            }
-        } else if id_on {
-            match &*unknown_length {
-                "and" => self.add_special(TokenType::And),
-                "or" => self.add_special(TokenType::Or),
-                "import" => self.add_special(TokenType::Import),
-                "return" => self.add_special(TokenType::Return),
-                "if" => self.add_special(TokenType::If),
-                "fun" => self.add_special(TokenType::Fun),
-                "true" => self.add_special_bare(TokenType::Boolean, "true".to_string()),
-                "false" => self.add_special_bare(TokenType::Boolean, "false".to_string()),
-                _ => {self.add_identifier(unknown_length.clone())}
-            }
+        if num_on || id_on {
+            self.add_pending(unknown_length, id_on, float);
         } else if str_on {
-            panic!("unclosed string at line {} char {}", self.tok_start_y, self.tok_start_x);
+            return Err(self.error("unclosed string".to_string()))
         }
+        self.pos_starter();
         self.add_special(TokenType::EndOfFile);
-        self.current_tokens.clone()
+        self.set_last_length(0);
+        Ok(self.current_tokens.clone())
     }
 
-    pub fn lex_text(&mut self, text: String) -> Vec<Token> {
+    pub fn lex_text(&mut self, text: String) -> Result<Vec<Token>, Diagnostic> {
         self.text_to_lex = text.chars().collect();
         self.lex()
     }
 }
 
-fn single_test(expected: Vec<(TokenType, String)>, to_lex: String){
-    let mut lexer_test = Lexer::new();
-    let start_lex =lexer_test.lex_text(to_lex);
-    let mut res_lex = vec![];
-    for token in start_lex {
-        res_lex.push((token.token_type, token.value))
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn single_test(expected: Vec<(TokenType, String)>, to_lex: String) {
+        let tokens = match Lexer::new().lex_text(to_lex) {
+            Ok(tokens) => tokens,
+            Err(error) => panic!("{}", error)
+        };
+        let lexed: Vec<(TokenType, String)> = tokens.into_iter().map(|token| (token.token_type, token.value)).collect();
+        assert_eq!(expected, lexed);
+    }
+
+    #[test]
+    fn variable() {
+        single_test( vec![
+            (TokenType::Identifier, "int".to_string()),
+            (TokenType::Identifier, "name".to_string()),
+            (TokenType::Integer, "123".to_string()),
+            (TokenType::EndLine, "".to_string()),
+            (TokenType::EndOfFile, "".to_string())
+        ],
+       r"int name 123;".to_string());
     }
-    let res = expected == res_lex;
-    if res {
-        println!("Test Passed: {:?}", res_lex)
-    } else {
-        println!("Test Failed, \n\texpected {:?} \n\tgot      {:?}", expected, res_lex)
+
+    #[test]
+    fn string() {
+        single_test( vec![
+            (TokenType::Identifier, "str".to_string()),
+            (TokenType::Identifier, "var".to_string()),
+            (TokenType::String, "hello".to_string()),
+            (TokenType::EndLine, "".to_string()),
+            (TokenType::EndOfFile, "".to_string())
+        ],
+       r#"str var "hello"; "#.to_string());
+    }
+
+    #[test]
+    fn condition() {
+        single_test( vec![
+            (TokenType::If, "".to_string()),
+            (TokenType::Identifier, "name".to_string()),
+            (TokenType::ComparisonOperation, "==".to_string()),
+            (TokenType::Integer, "123".to_string()),
+            (TokenType::CurlyBracketOpen, "".to_string()),
+            (TokenType::CurlyBracketClose, "".to_string()),
+            (TokenType::EndOfFile, "".to_string()),
+        ],
+       r"if name == 123{}".to_string());
+    }
+
+    #[test]
+    fn program() {
+        single_test( vec![
+            (TokenType::Identifier, "int".to_string()),
+            (TokenType::Identifier, "name".to_string()),
+            (TokenType::Integer, "123".to_string()),
+            (TokenType::EndLine, "".to_string()),
+            (TokenType::If, "".to_string()),
+            (TokenType::Identifier, "name".to_string()),
+            (TokenType::ComparisonOperation, ">".to_string()),
+            (TokenType::Integer, "100".to_string()),
+            (TokenType::CurlyBracketOpen, "".to_string()),
+            (TokenType::Identifier, "out".to_string()),
+            (TokenType::ParenthesisOpen, "".to_string()),
+            (TokenType::Identifier, "name".to_string()),
+            (TokenType::ParenthesisClose, "".to_string()),
+            (TokenType::EndLine, "".to_string()),
+            (TokenType::CurlyBracketClose, "".to_string()),
+            (TokenType::Else, "".to_string()),
+            (TokenType::If, "".to_string()),
+            (TokenType::Identifier, "name".to_string()),
+            (TokenType::ComparisonOperation, "<".to_string()),
+            (TokenType::Integer, "123".to_string()),
+            (TokenType::Or, "".to_string()),
+            (TokenType::Identifier, "name".to_string()),
+            (TokenType::ComparisonOperation, "<".to_string()),
+            (TokenType::Integer, "123".to_string()),
+            (TokenType::And, "".to_string()),
+            (TokenType::Identifier, "name".to_string()),
+            (TokenType::ComparisonOperation, "<".to_string()),
+            (TokenType::Integer, "123".to_string()),
+            (TokenType::CurlyBracketOpen, "".to_string()),
+            (TokenType::Identifier, "out".to_string()),
+            (TokenType::ParenthesisOpen, "".to_string()),
+            (TokenType::String, "huh?".to_string()),
+            (TokenType::ParenthesisClose, "".to_string()),
+            (TokenType::EndLine, "".to_string()),
+            (TokenType::CurlyBracketClose, "".to_string()),
+            (TokenType::Fun, "".to_string()),
+            (TokenType::Identifier, "make".to_string()),
+            (TokenType::ParenthesisOpen, "".to_string()),
+            (TokenType::ParenthesisClose, "".to_string()),
+            (TokenType::Identifier, "int".to_string()),
+            (TokenType::CurlyBracketOpen, "".to_string()),
+            (TokenType::Return, "".to_string()),
+            (TokenType::Integer, "123".to_string()),
+            (TokenType::EndLine, "".to_string()),
+            (TokenType::CurlyBracketClose, "".to_string()),
+            (TokenType::EndOfFile, "".to_string()),
+        ],
+       r#"
+       int name 123;
+       if name > 100 {
+          out(name);
+       } else if name < 123 or name < 123 and name < 123 {
+            out("huh?");
+       }
+       fun make() int {
+            return 123;
+       }
+       "#.to_string());
+    }
+
+    #[test]
+    fn while_loop() {
+        single_test( vec![
+            (TokenType::While, "".to_string()),
+            (TokenType::Identifier, "name".to_string()),
+            (TokenType::ComparisonOperation, "<".to_string()),
+            (TokenType::Integer, "10".to_string()),
+            (TokenType::CurlyBracketOpen, "".to_string()),
+            (TokenType::If, "".to_string()),
+            (TokenType::Identifier, "name".to_string()),
+            (TokenType::ComparisonOperation, "==".to_string()),
+            (TokenType::Integer, "5".to_string()),
+            (TokenType::CurlyBracketOpen, "".to_string()),
+            (TokenType::Break, "".to_string()),
+            (TokenType::EndLine, "".to_string()),
+            (TokenType::CurlyBracketClose, "".to_string()),
+            (TokenType::Continue, "".to_string()),
+            (TokenType::EndLine, "".to_string()),
+            (TokenType::CurlyBracketClose, "".to_string()),
+            (TokenType::EndOfFile, "".to_string()),
+        ],
+       r#"
+       while name < 10 {
+            if name == 5 {
+                break;
+            }
+            continue;
+       }
+       "#.to_string());
+    }
+
+    #[test]
+    fn for_loop() {
+        single_test( vec![
+            (TokenType::For, "".to_string()),
+            (TokenType::Identifier, "i".to_string()),
+            (TokenType::In, "".to_string()),
+            (TokenType::Integer, "0".to_string()),
+            (TokenType::Range, "".to_string()),
+            (TokenType::Identifier, "n".to_string()),
+            (TokenType::Step, "".to_string()),
+            (TokenType::Integer, "2".to_string()),
+            (TokenType::CurlyBracketOpen, "".to_string()),
+            (TokenType::CurlyBracketClose, "".to_string()),
+            (TokenType::For, "".to_string()),
+            (TokenType::Identifier, "j".to_string()),
+            (TokenType::In, "".to_string()),
+            (TokenType::Integer, "1".to_string()),
+            (TokenType::Range, "".to_string()),
+            (TokenType::Integer, "10".to_string()),
+            (TokenType::CurlyBracketOpen, "".to_string()),
+            (TokenType::CurlyBracketClose, "".to_string()),
+            (TokenType::EndOfFile, "".to_string()),
+        ],
+       r#"
+       for i in 0..n step 2 {}
+       for j in 1..10 {}
+       "#.to_string());
+    }
+
+    #[test]
+    fn chars() {
+        single_test( vec![
+            (TokenType::Identifier, "char".to_string()),
+            (TokenType::Identifier, "c".to_string()),
+            (TokenType::AssignmentArrow, "".to_string()),
+            (TokenType::Character, "a".to_string()),
+            (TokenType::EndLine, "".to_string()),
+            (TokenType::Identifier, "out".to_string()),
+            (TokenType::ParenthesisOpen, "".to_string()),
+            (TokenType::Character, "\n".to_string()),
+            (TokenType::SeperatorComma, "".to_string()),
+            (TokenType::Character, "'".to_string()),
+            (TokenType::ParenthesisClose, "".to_string()),
+            (TokenType::EndLine, "".to_string()),
+            (TokenType::EndOfFile, "".to_string()),
+        ],
+       r#"
+       char c <- 'a';
+       out('\n', '\'');
+       "#.to_string());
     }
-}
 
-pub fn lexer_test(){
-    println!("Running lexer tests..");
-    // (TokenType::, "".to_string())
-
-   //   single_test( vec![
-   //      (TokenType::, "".to_string()),
-   //      (TokenType::, "".to_string()),
-   //      (TokenType::, "".to_string()),
-   //      (TokenType::, "".to_string()),
-   //      (TokenType::, "".to_string()),
-   //  ],
-   // r#""#.to_string());
-
-    single_test( vec![
-        (TokenType::Identifier, "int".to_string()),
-        (TokenType::Identifier, "name".to_string()),
-        (TokenType::Integer, "123".to_string()),
-        (TokenType::EndLine, "".to_string()),
-        (TokenType::EndOfFile, "".to_string())
-    ],
-   r"int name 123;".to_string());
-
-    single_test( vec![
-        (TokenType::Identifier, "str".to_string()),
-        (TokenType::Identifier, "var".to_string()),
-        (TokenType::String, "hello".to_string()),
-        (TokenType::EndLine, "".to_string()),
-        (TokenType::EndOfFile, "".to_string())
-    ],
-   r#"str var "hello"; "#.to_string());
-
-    single_test( vec![
-        (TokenType::If, "".to_string()),
-        (TokenType::Identifier, "name".to_string()),
-        (TokenType::ComparisonOperation, "==".to_string()),
-        (TokenType::Integer, "123".to_string()),
-        (TokenType::CurlyBracketOpen, "".to_string()),
-        (TokenType::CurlyBracketClose, "".to_string()),
-        (TokenType::EndOfFile, "".to_string()),
-    ],
-   r"if name == 123{}".to_string());
-
-     single_test( vec![
-        (TokenType::Identifier, "int".to_string()),
-        (TokenType::Identifier, "name".to_string()),
-        (TokenType::Integer, "123".to_string()),
-        (TokenType::EndLine, "".to_string()),
-        (TokenType::If, "".to_string()),
-        (TokenType::Identifier, "name".to_string()),
-        (TokenType::ComparisonOperation, ">".to_string()),
-        (TokenType::Integer, "100".to_string()),
-        (TokenType::CurlyBracketOpen, "".to_string()),
-        (TokenType::Identifier, "out".to_string()),
-        (TokenType::ParenthesisOpen, "".to_string()),
-        (TokenType::Identifier, "name".to_string()),
-        (TokenType::ParenthesisClose, "".to_string()),
-        (TokenType::EndLine, "".to_string()),
-        (TokenType::CurlyBracketClose, "".to_string()),
-        (TokenType::Else, "".to_string()),
-        (TokenType::If, "".to_string()),
-        (TokenType::Identifier, "name".to_string()),
-        (TokenType::ComparisonOperation, "<".to_string()),
-        (TokenType::Integer, "123".to_string()),
-        (TokenType::Or, "".to_string()),
-        (TokenType::Identifier, "name".to_string()),
-        (TokenType::ComparisonOperation, "<".to_string()),
-        (TokenType::Integer, "123".to_string()),
-        (TokenType::And, "".to_string()),
-        (TokenType::Identifier, "name".to_string()),
-        (TokenType::ComparisonOperation, "<".to_string()),
-        (TokenType::Integer, "123".to_string()),
-        (TokenType::CurlyBracketOpen, "".to_string()),
-        (TokenType::Identifier, "out".to_string()),
-        (TokenType::ParenthesisOpen, "".to_string()),
-        (TokenType::String, "huh?".to_string()),
-        (TokenType::ParenthesisClose, "".to_string()),
-        (TokenType::EndLine, "".to_string()),
-        (TokenType::CurlyBracketClose, "".to_string()),
-        (TokenType::Fun, "".to_string()),
-        (TokenType::Identifier, "make".to_string()),
-        (TokenType::ParenthesisOpen, "".to_string()),
-        (TokenType::ParenthesisClose, "".to_string()),
-        (TokenType::Identifier, "int".to_string()),
-        (TokenType::CurlyBracketOpen, "".to_string()),
-        (TokenType::Return, "".to_string()),
-        (TokenType::Integer, "123".to_string()),
-        (TokenType::EndLine, "".to_string()),
-        (TokenType::CurlyBracketClose, "".to_string()),
-        (TokenType::EndOfFile, "".to_string()),
-    ],
-   r#"
-   int name 123;
-   if name > 100 {
-      out(name);
-   } else if name < 123 or name < 123 and name < 123 {
-        out("huh?");
-   }
-   fun make() int {
-        return 123;
-   }
-   "#.to_string());
-
-    println!("Lexer tests complete");
-}
\ No newline at end of file
+    #[test]
+    fn string_right_after_a_token() {
+        single_test( vec![
+            (TokenType::Identifier, "out".to_string()),
+            (TokenType::ParenthesisOpen, "".to_string()),
+            (TokenType::Integer, "1".to_string()),
+            (TokenType::String, "a".to_string()),
+            (TokenType::Identifier, "b".to_string()),
+            (TokenType::String, "c".to_string()),
+            (TokenType::FloatingPoint, "2.5".to_string()),
+            (TokenType::String, "d".to_string()),
+            (TokenType::ParenthesisClose, "".to_string()),
+            (TokenType::EndLine, "".to_string()),
+            (TokenType::EndOfFile, "".to_string()),
+        ],
+       r#"out(1"a"b"c"2.5"d");"#.to_string());
+    }
+
+    #[test]
+    fn unknown_characters_are_errors() {
+        for text in ["int a <- 1 $ 2;", "a = 1;", "!a", "'ab'", "\"open"] {
+            assert!(Lexer::new().lex_text(text.to_string()).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn escapes() {
+        single_test( vec![
+            (TokenType::String, "say \"hi\"\tand\\leave\n\0|\r|'{}\u{1F600}é".to_string()),
+            (TokenType::EndOfFile, "".to_string()),
+        ],
+       r#""say \"hi\"\tand\\leave\n\0|\r|\'\{\}\u{1F600}\u{e9}""#.to_string());
+    }
+
+    #[test]
+    fn raw_strings() {
+        single_test( vec![
+            (TokenType::String, r"C:\new\{folder}".to_string()),
+            (TokenType::String, "raw\nlines".to_string()),
+            (TokenType::EndOfFile, "".to_string()),
+        ],
+       "r\"C:\\new\\{folder}\" r\"raw\nlines\"".to_string());
+    }
+
+    #[test]
+    fn multi_line_strings() {
+        let tokens = Lexer::new().lex_text("str a <- \"one\ntwo\";\nint b".to_string()).unwrap();
+        assert_eq!(tokens[3].value, "one\ntwo");
+        // tokens after the string know which line they are on
+        let b = tokens.iter().find(|token| token.value == "b").unwrap();
+        assert_eq!((b.y, b.x), (3, 5));
+    }
+
+    #[test]
+    fn bad_escapes_are_errors() {
+        for text in [r#""\q""#, r#""\u41""#, r#""\u{110000}""#, r#""\u{zz}""#, r"'\q'", r#"r"a\"b""#] {
+            assert!(Lexer::new().lex_text(text.to_string()).is_err(), "{}", text);
+        }
+    }
+}
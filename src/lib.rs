@@ -0,0 +1,44 @@
+// diagnostics are only built once something went wrong, so their size doesn't matter
+#![allow(clippy::result_large_err)]
+
+//! runs SL scripts inside a rust program
+//!
+//! ```
+//! use sl::{Engine, Value};
+//!
+//! let mut engine = Engine::new();
+//! engine.set_global("width", Value::Int(4)).unwrap();
+//! assert_eq!(engine.eval("int area <- width * width; area + 1"), Ok(Value::Int(17)));
+//! assert_eq!(engine.get_global("area"), Some(Value::Int(16)));
+//! ```
+//!
+//! an `Engine` stays on the thread that made it, it isn't `Send` since outputs and registered
+//! functions are shared through `Rc`, a program with several threads makes one engine in each
+//!
+//! ```compile_fail
+//! let engine = sl::Engine::new();
+//! std::thread::spawn(move || engine);
+//! ```
+
+mod lexer;
+mod parser;
+mod ast;
+mod check;
+mod sl;
+mod error;
+mod interpreter;
+mod repl;
+mod engine;
+mod native;
+mod bigint;
+// the command line program, main.rs only calls into it
+#[doc(hidden)]
+pub mod cli;
+
+pub use bigint::BigInt;
+pub use engine::Engine;
+pub use error::{Diagnostic, Emitter, ErrorKind, Label, Span};
+pub use interpreter::{Overflow, Value, CALL_LIMIT};
+pub use check::ArgTypes;
+pub use native::{native_error, Context, Input, NativeFn, NativeFunction, Natives, Output};
+pub use parser::{IntType, VarTypes};
@@ -1,20 +1,11 @@
-extern crate core;
-
-use crate::lexer::lexer_test;
-use crate::parser::Parsed;
-
-mod util {
-    pub mod eval;
-    pub mod eval_lexer;
-}
-mod lexer;
-mod parser;
-mod ast;
-mod check;
-mod sl;
-mod error;
-mod interpreter;
+// scripts run on their own thread so debug builds have room for the deepest calls the interpreter allows
+const STACK_SIZE: usize = 64 << 20;
 
 fn main() {
-    sl::interpret("main.sl".to_string(), false)
+    let arguments = std::env::args().skip(1).collect();
+    let runner = std::thread::Builder::new().stack_size(STACK_SIZE).spawn(|| sl::cli::main(arguments));
+    let code = runner.expect("couldn't start the interpreter thread").join().unwrap_or(1);
+    // exiting skips the destructors that would flush stdout
+    let _ = std::io::Write::flush(&mut std::io::stdout());
+    std::process::exit(code)
 }
@@ -0,0 +1,167 @@
+use std::cell::{RefCell, RefMut};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{BufRead, BufReader, Write};
+use std::rc::Rc;
+use crate::check::ArgTypes;
+use crate::error::{Diagnostic, ErrorKind, Span};
+use crate::interpreter::Value;
+use crate::parser::VarTypes;
+
+// where scripts write to and read from, shared so the host can keep a handle to a buffer
+pub type Output = Rc<RefCell<dyn Write>>;
+pub type Input = Rc<RefCell<dyn BufRead>>;
+
+// what a native function can see of the interpreter running it
+#[derive(Clone)]
+pub struct Context {
+    // arguments passed to the script on the command line
+    arguments: Vec<String>,
+    // stdout, stderr and stdin unless the host replaced them
+    output: Output,
+    error_output: Output,
+    input: Input,
+}
+
+impl Context {
+    pub(crate) fn new() -> Self {
+        Self {
+            arguments: vec![],
+            output: Rc::new(RefCell::new(std::io::stdout())),
+            error_output: Rc::new(RefCell::new(std::io::stderr())),
+            input: Rc::new(RefCell::new(BufReader::new(std::io::stdin())))
+        }
+    }
+    pub(crate) fn set_arguments(&mut self, arguments: Vec<String>) {
+        self.arguments = arguments;
+    }
+    pub(crate) fn set_output(&mut self, output: Output) {
+        self.output = output;
+    }
+    pub(crate) fn set_error_output(&mut self, error_output: Output) {
+        self.error_output = error_output;
+    }
+    pub(crate) fn set_input(&mut self, input: Input) {
+        self.input = input;
+    }
+    pub fn arguments(&self) -> &[String] {
+        &self.arguments
+    }
+    pub fn output(&self) -> RefMut<'_, dyn Write> {
+        self.output.borrow_mut()
+    }
+    pub fn error_output(&self) -> RefMut<'_, dyn Write> {
+        self.error_output.borrow_mut()
+    }
+    pub fn input(&self) -> RefMut<'_, dyn BufRead> {
+        self.input.borrow_mut()
+    }
+}
+
+// gets the already evaluated arguments, `Value::Void` is returned by functions without a return type.
+// errors without a position are shown at the call
+pub type NativeFn = dyn Fn(&mut Context, Vec<Value>) -> Result<Value, Diagnostic>;
+
+#[derive(Clone)]
+pub struct NativeFunction {
+    pub arguments: Vec<ArgTypes>,
+    // empty when nothing is returned, like function definitions
+    pub return_type: Vec<VarTypes>,
+    pub function: Rc<NativeFn>,
+}
+
+impl NativeFunction {
+    pub fn new<F>(arguments: Vec<ArgTypes>, return_type: Option<VarTypes>, function: F) -> Self
+        where F: Fn(&mut Context, Vec<Value>) -> Result<Value, Diagnostic> + 'static
+    {
+        Self {
+            arguments,
+            return_type: return_type.into_iter().collect(),
+            function: Rc::new(function)
+        }
+    }
+}
+
+impl fmt::Debug for NativeFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "NativeFunction({:?}) -> {:?}", self.arguments, self.return_type)
+    }
+}
+
+// functions written in rust that scripts can call, the checker takes their
+// signatures and the interpreter calls them
+#[derive(Clone, Default, Debug)]
+pub struct Natives {
+    functions: HashMap<String, NativeFunction>,
+}
+
+pub fn native_error(message: String) -> Diagnostic {
+    Diagnostic::new(ErrorKind::Runtime, message, Span::new(0, 0, 0))
+}
+
+fn int(value: &Value) -> i128 {
+    // the checker made sure of the type
+    match value {
+        Value::Int(value) => *value,
+        value => panic!("huh? what? Expected Int got {:?}", value)
+    }
+}
+
+impl Natives {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    // the functions every script has
+    pub fn standard() -> Self {
+        let mut natives = Self::new();
+        natives.register("out", vec![ArgTypes::Any], None, |context, arguments| {
+            writeln!(context.output(), "{}", arguments[0])
+                .map_err(|error| native_error(format!("couldn't write the output: {}", error)))?;
+            Ok(Value::Void)
+        });
+        natives.register("arg", vec![ArgTypes::Int], Some(VarTypes::Str), |context, arguments| {
+            let index = int(&arguments[0]);
+            match usize::try_from(index).ok().and_then(|index| context.arguments().get(index)) {
+                Some(argument) => Ok(Value::String(argument.clone())),
+                None => Err(native_error(format!("there is no argument {}, {} were given", index, context.arguments().len())))
+            }
+        });
+        natives.register("arg_count", vec![], Some(VarTypes::Int), |context, _| {
+            Ok(Value::Int(context.arguments().len() as i128))
+        });
+        // strings count chars, the same thing their indexes count
+        natives.register("len", vec![ArgTypes::OneOf(vec![ArgTypes::Array(Box::new(ArgTypes::Any)), ArgTypes::Str])], Some(VarTypes::Int), |_, arguments| {
+            match &arguments[0] {
+                Value::Array(elements) => Ok(Value::Int(elements.len() as i128)),
+                Value::String(text) => Ok(Value::Int(text.chars().count() as i128)),
+                value => panic!("huh? what? Expected an array or a string got {:?}", value)
+            }
+        });
+        // stops the script with a status code
+        natives.register("exit", vec![ArgTypes::Int], None, |_, arguments| {
+            // the os only keeps the lowest 8 bits, 256 would look like a success
+            let code = int(&arguments[0]);
+            match u8::try_from(code) {
+                Ok(code) => Err(Diagnostic::new(ErrorKind::Exit(code as i32), "exit".to_string(), Span::new(0, 0, 0))),
+                Err(_) => Err(native_error(format!("exit code {} is out of range", code))
+                    .with_note("exit codes go from 0 to 255".to_string()))
+            }
+        });
+        natives
+    }
+    // replaces a function with the same name
+    pub fn register<F>(&mut self, name: &str, arguments: Vec<ArgTypes>, return_type: Option<VarTypes>, function: F)
+        where F: Fn(&mut Context, Vec<Value>) -> Result<Value, Diagnostic> + 'static
+    {
+        self.functions.insert(name.to_string(), NativeFunction::new(arguments, return_type, function));
+    }
+    pub(crate) fn insert(&mut self, name: String, native: NativeFunction) {
+        self.functions.insert(name, native);
+    }
+    pub fn get(&self, name: &str) -> Option<&NativeFunction> {
+        self.functions.get(name)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &NativeFunction)> {
+        self.functions.iter()
+    }
+}
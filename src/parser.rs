@@ -1,43 +1,143 @@
+use std::fmt;
+use crate::ast::{Expr, Math};
 use crate::lexer::{Lexer, Token, TokenType};
+use crate::error::{Diagnostic, ErrorKind, Span};
 
 
-#[derive(Debug, Clone, PartialEq)]
+// ints with a fixed size, `int` itself is an i128
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd)]
+pub enum IntType {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl IntType {
+    fn bits(self) -> u32 {
+        match self {
+            IntType::I8 | IntType::U8 => 8,
+            IntType::I16 | IntType::U16 => 16,
+            IntType::I32 | IntType::U32 => 32,
+            IntType::I64 | IntType::U64 => 64,
+        }
+    }
+    fn signed(self) -> bool {
+        matches!(self, IntType::I8 | IntType::I16 | IntType::I32 | IntType::I64)
+    }
+    pub fn min(self) -> i128 {
+        if self.signed() { -(1 << (self.bits() - 1)) } else { 0 }
+    }
+    pub fn max(self) -> i128 {
+        if self.signed() { (1 << (self.bits() - 1)) - 1 } else { (1 << self.bits()) - 1 }
+    }
+    pub fn contains(self, value: i128) -> bool {
+        self.min() <= value && value <= self.max()
+    }
+    // keeps the lowest bits like a two's complement int of this size would, 300 is 44 as a u8
+    pub fn wrap(self, value: i128) -> i128 {
+        let low_bits = value & ((1 << self.bits()) - 1);
+        if self.signed() && low_bits > self.max() { low_bits - (1 << self.bits()) } else { low_bits }
+    }
+    pub fn saturate(self, value: i128) -> i128 {
+        value.clamp(self.min(), self.max())
+    }
+}
+
+#[derive(Clone, PartialEq)]
 pub enum VarTypes {
     Int,
+    Sized(IntType),
+    // an int of any size
+    BigInt,
     Str,
+    // one unicode character
+    Char,
     Bool,
     Float,
-    Struct,
+    // element type
+    Array(Box<VarTypes>),
+    // name of the struct, structs with the same fields are still different types
+    Struct(String),
+    // only used by the checker, for bindings whose value had an error so their uses aren't reported again
+    Error,
 }
 
+impl fmt::Debug for VarTypes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // arrays are shown the way they are written, `[Int]`
+        match self {
+            VarTypes::Int => write!(f, "Int"),
+            VarTypes::Sized(int_type) => write!(f, "{:?}", int_type),
+            VarTypes::BigInt => write!(f, "BigInt"),
+            VarTypes::Str => write!(f, "Str"),
+            VarTypes::Char => write!(f, "Char"),
+            VarTypes::Bool => write!(f, "Bool"),
+            VarTypes::Float => write!(f, "Float"),
+            VarTypes::Array(element_type) => write!(f, "[{:?}]", element_type),
+            VarTypes::Struct(name) => write!(f, "{}", name),
+            VarTypes::Error => write!(f, "Error"),
+        }
+    }
+}
+
+const VARIABLE_TYPES: [&str; 14] = ["int", "str", "char", "bool", "float", "i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64", "bigint"];
+const ALLOWED_TOKENS_IN_EVALUATION: [TokenType; 13] = [
+    TokenType::Identifier, TokenType::MathOperation, TokenType::ParenthesisOpen,
+    TokenType::ParenthesisClose, TokenType::ComparisonOperation, TokenType::SeperatorComma,
+    TokenType::BracketOpen, TokenType::BracketClose, TokenType::DirectMemberSelection,
+    TokenType::And, TokenType::Or, TokenType::Not, TokenType::As
+];
 
 pub fn str_to_types(from: String) -> Option<VarTypes>{
     match &*from {
         "int" => Some(VarTypes::Int),
+        "i8" => Some(VarTypes::Sized(IntType::I8)),
+        "i16" => Some(VarTypes::Sized(IntType::I16)),
+        "i32" => Some(VarTypes::Sized(IntType::I32)),
+        "i64" => Some(VarTypes::Sized(IntType::I64)),
+        "u8" => Some(VarTypes::Sized(IntType::U8)),
+        "u16" => Some(VarTypes::Sized(IntType::U16)),
+        "u32" => Some(VarTypes::Sized(IntType::U32)),
+        "u64" => Some(VarTypes::Sized(IntType::U64)),
+        "bigint" => Some(VarTypes::BigInt),
         "str" => Some(VarTypes::Str),
+        "char" => Some(VarTypes::Char),
         "bool" => Some(VarTypes::Bool),
         "float" => Some(VarTypes::Float),
         _ => {None}
     }
 }
 
-pub fn data_token_type_to_types(from: &TokenType) -> Option<VarTypes> {
-    match *from {
-        TokenType::Integer => Some(VarTypes::Int),
-        TokenType::String => Some(VarTypes::Str),
-        TokenType::Boolean => Some(VarTypes::Bool),
-        TokenType::FloatingPoint => Some(VarTypes::Float),
-        _ => { None }
-    }
-}
-
 #[derive(Debug, Clone)]
 pub enum Parsed {
-    VariableAssignment(Token, VarTypes, Vec<Token>),
-    VariableReassignment(Token, Vec<Token>),
+    VariableAssignment(Token, VarTypes, Expr),
+    VariableReassignment(Token, Expr),
+    // `a[i] <- v` or `p.x <- v`, the target is an index or field expression on a variable
+    ElementReassignment(Expr, Expr),
     Program(Vec<Parsed>),
-    FuncCall(Token, Vec<Token>),
-    Conditions(Vec<(Vec<Parsed>, Vec<Token>, (u32, u32))>),
+    FuncCall(Token, Vec<Expr>),
+    Conditions(Vec<(Vec<Parsed>, Expr, (u32, u32))>),
+    //                 name   arguments              return type   body
+    FunctionDefinition(Token, Vec<(Token, VarTypes)>, Vec<VarTypes>, Vec<Parsed>),
+    //               name   fields
+    StructDefinition(Token, Vec<(Token, VarTypes)>),
+    // struct name, function definitions, the ones whose first argument is `self` are methods
+    Impl(Token, Vec<Parsed>),
+    // `p.move(1);`, an Expr::MethodCall whose value isn't used
+    MethodCall(Expr),
+    Return(Token, Option<Expr>),
+    While(Expr, Vec<Parsed>, (u32, u32)),
+    //  variable start end   step (None means 1)  body
+    For(Token, Expr, Expr, Option<Expr>, Vec<Parsed>),
+    Break(Token),
+    Continue(Token),
+    // stands in for a block whose header had a syntax error, only exists while parsing fails
+    Recovered(Vec<Parsed>, (u32, u32)),
     // ElseIf(),
     // Else(),
 }
@@ -52,6 +152,7 @@ pub struct Parser {
     current_token: Token,
     to_parse_tokens: Vec<Token>,
     scope: Vec<Parsed>,
+    errors: Vec<Diagnostic>,
     debug: bool
 }
 
@@ -64,6 +165,7 @@ impl Parser {
             current_token: Token::new(TokenType::NullForParser, "".to_string()),
             to_parse_tokens: vec![],
             scope: vec![Parsed::Program(vec![])],
+            errors: vec![],
             debug
         }
     }
@@ -84,8 +186,8 @@ impl Parser {
             Some(self.to_parse_tokens[ind as usize].clone())
         }
     }
-    pub fn parse_text(&mut self, text: String) -> Parsed{
-        self.to_parse_tokens = self.lexer.lex_text(text);
+    pub fn parse_text(&mut self, text: String) -> Result<Parsed, Vec<Diagnostic>> {
+        self.to_parse_tokens = self.lexer.lex_text(text).map_err(|error| vec![error])?;
         if self.debug {
             println!("--------------------------------------------------------");
             for tok in &self.to_parse_tokens {
@@ -95,8 +197,69 @@ impl Parser {
         }
         self.parse()
     }
-    fn error(&self, error: String){
-        panic!("{}, at line {} char {}", error, self.current_token.y, self.current_token.x)
+    pub fn parse_expression_text(&mut self, text: String) -> Result<Expr, Vec<Diagnostic>> {
+        // a single expression without a statement around it, like `a + 1`
+        let mut tokens = self.lexer.lex_text(text).map_err(|error| vec![error])?;
+        if let Some(end_of_file) = tokens.pop() {
+            self.current_token = end_of_file;
+        }
+        self.to_expression(tokens).map_err(|error| vec![error])
+    }
+    pub fn parse_with_tail(&mut self, text: String) -> Result<(Parsed, Option<Expr>), Vec<Diagnostic>> {
+        // statements that can be followed by one expression without a `;`, like `int a <- 1; a + 1`
+        let mut tokens = self.lexer.lex_text(text).map_err(|error| vec![error])?;
+        let end_of_file = tokens.pop().expect("the lexer always adds an end of file token");
+
+        // the tail starts after the last `;` or `}` that isn't inside a block
+        let mut depth = 0;
+        let mut tail_start = 0;
+        for (index, token) in tokens.iter().enumerate() {
+            match token.token_type {
+                TokenType::CurlyBracketOpen => depth += 1,
+                TokenType::CurlyBracketClose => {
+                    depth -= 1;
+                    if depth == 0 {
+                        tail_start = index + 1
+                    }
+                },
+                TokenType::EndLine if depth == 0 => tail_start = index + 1,
+                _ => {}
+            }
+        }
+        // unbalanced blocks are left to the statement parser to report
+        if depth != 0 {
+            tail_start = tokens.len()
+        }
+        let mut tail = tokens.split_off(tail_start);
+        // `} else {` still belongs to the if before it
+        if tail.first().is_some_and(|token| token.token_type == TokenType::Else) {
+            tokens.append(&mut tail);
+        }
+        tokens.push(end_of_file.clone());
+        self.to_parse_tokens = tokens;
+        let parsed = self.parse();
+
+        self.current_token = end_of_file;
+        let tail = if tail.is_empty() { None } else { Some(self.to_expression(tail)) };
+        match (parsed, tail) {
+            (Ok(parsed), None) => Ok((parsed, None)),
+            (Ok(parsed), Some(Ok(tail))) => Ok((parsed, Some(tail))),
+            (Ok(_), Some(Err(error))) => Err(vec![error]),
+            (Err(mut errors), Some(Err(error))) => {
+                errors.push(error);
+                Err(errors)
+            },
+            (Err(errors), _) => Err(errors),
+        }
+    }
+    fn error(&self, error: String) -> Diagnostic {
+        Diagnostic::at_token(ErrorKind::Syntax, error, &self.current_token)
+    }
+    fn to_expression(&self, tokens: Vec<Token>) -> Result<Expr, Diagnostic> {
+        if tokens.is_empty() {
+            return Err(self.error("Expected an expression".to_string()))
+        }
+        Math::new().parse(tokens)
     }
     fn add_to_top_of_stack(&mut self, to_push: Parsed){
         let ind = self.scope.len() - 1;
@@ -108,203 +271,549 @@ impl Parser {
                 let ind = if_block.len();
                 if_block[ind - 1].0.push(to_push)
             },
+            Parsed::FunctionDefinition(.., body) => body.push(to_push),
+            Parsed::While(_, body, _) => body.push(to_push),
+            Parsed::For(.., body) => body.push(to_push),
+            Parsed::Recovered(body, _) => body.push(to_push),
+            Parsed::Impl(_, functions) => functions.push(to_push),
             _ => {unimplemented!()}
         }
     }
-    fn add_var(&mut self, var_name: Token, var_type: VarTypes, values: Vec<Token>){
-        self.add_to_top_of_stack(Parsed::VariableAssignment(var_name, var_type, values))
+    fn add_var(&mut self, var_name: Token, var_type: VarTypes, values: Vec<Token>) -> Result<(), Diagnostic> {
+        let value = self.to_expression(values)?;
+        self.add_to_top_of_stack(Parsed::VariableAssignment(var_name, var_type, value));
+        Ok(())
+    }
+    fn reassign_var(&mut self, var_name: Token, values: Vec<Token>) -> Result<(), Diagnostic> {
+        let value = self.to_expression(values)?;
+        self.add_to_top_of_stack(Parsed::VariableReassignment(var_name, value));
+        Ok(())
+    }
+    fn reassign_element(&mut self, target: Vec<Token>, values: Vec<Token>) -> Result<(), Diagnostic> {
+        let target = self.to_expression(target)?;
+        if !matches!(target.root(), Expr::Variable(_)) {
+            return Err(self.error("Expected a variable or an element of one before '<-'".to_string()))
+        }
+        let value = self.to_expression(values)?;
+        self.add_to_top_of_stack(Parsed::ElementReassignment(target, value));
+        Ok(())
+    }
+    fn add_method_call(&mut self, call: Vec<Token>) -> Result<(), Diagnostic> {
+        match self.to_expression(call)? {
+            call @ Expr::MethodCall(..) => self.add_to_top_of_stack(Parsed::MethodCall(call)),
+            _ => return Err(self.error("Expected a variable assignment operator '<-'".to_string()))
+        }
+        Ok(())
+    }
+    fn add_func_call(&mut self, call: Vec<Token>) -> Result<(), Diagnostic> {
+        match self.to_expression(call)? {
+            Expr::Call(func_name, args) => self.add_to_top_of_stack(Parsed::FuncCall(func_name, args)),
+            _ => return Err(self.error("Expected a function call".to_string()))
+        }
+        Ok(())
     }
-    fn reassign_var(&mut self, var_name: Token, values: Vec<Token>){
-        self.add_to_top_of_stack(Parsed::VariableReassignment(var_name, values))
+    fn add_function(&mut self, func_name: Token, args: Vec<(Token, VarTypes)>, return_type: Vec<VarTypes>){
+        self.scope.push(Parsed::FunctionDefinition(func_name, args, return_type, vec![]))
     }
-    fn add_func_call(&mut self, func_name: Token, args: Vec<Token>){
-        self.add_to_top_of_stack(Parsed::FuncCall(func_name, args))
+    fn add_return(&mut self, return_token: Token, values: Vec<Token>) -> Result<(), Diagnostic> {
+        let value = if values.is_empty() { None } else { Some(self.to_expression(values)?) };
+        self.add_to_top_of_stack(Parsed::Return(return_token, value));
+        Ok(())
     }
-    fn add_if(&mut self, condition: Vec<Token>, loc: (u32, u32)){
-        self.scope.push(Parsed::Conditions(vec![(vec![], condition, loc)]))
+    fn add_while(&mut self, condition: Vec<Token>, loc: (u32, u32)) -> Result<(), Diagnostic> {
+        let condition = self.to_expression(condition)?;
+        self.scope.push(Parsed::While(condition, vec![], loc));
+        Ok(())
     }
-    fn add_else(&mut self, condition: Vec<Token>, loc: (u32, u32)){
+    fn add_for(&mut self, variable: Token, start: Vec<Token>, end: Vec<Token>, step: Vec<Token>) -> Result<(), Diagnostic> {
+        let start = self.to_expression(start)?;
+        let end = self.to_expression(end)?;
+        let step = if step.is_empty() { None } else { Some(self.to_expression(step)?) };
+        self.scope.push(Parsed::For(variable, start, end, step, vec![]));
+        Ok(())
+    }
+    fn add_if(&mut self, condition: Vec<Token>, loc: (u32, u32)) -> Result<(), Diagnostic> {
+        let condition = self.to_expression(condition)?;
+        self.scope.push(Parsed::Conditions(vec![(vec![], condition, loc)]));
+        Ok(())
+    }
+    fn add_else(&mut self, condition: Vec<Token>, loc: (u32, u32)) -> Result<(), Diagnostic> {
+        let condition = self.to_expression(condition)?;
         let ind = self.scope.len() - 1;
         let d = &mut self.scope[ind];
         match d {
-            Parsed::Program(_whole_program) => self.error("Unexpected Else Block".to_string()),
             Parsed::Conditions(if_block, ..) => {
                 // [(stuff, cond, (pos)), (stuff, cond, (pos))]
                 if_block.push((vec![], condition, loc))
             },
-            _ => {unimplemented!()}
+            // the if had a syntax error, its else blocks are parsed into the same placeholder
+            Parsed::Recovered(..) => {},
+            _ => return Err(self.error("Unexpected Else Block".to_string()))
         }
+        Ok(())
     }
     fn un_scope(&mut self) {
         let block = self.scope.pop().expect("Stack had 1 element which was probably the program");
         self.add_to_top_of_stack(block);
     }
-    fn parse(&mut self) -> Parsed {
-        let variable_types = vec!["int".to_string(), "str".to_string(), "bool".to_string(), "float".to_string()];
-        let allowed_tokens_in_evaluation = vec![
-            TokenType::Identifier, TokenType::MathOperation, TokenType::ParenthesisClose,
-            TokenType::ComparisonOperation
-        ];
-        while self.run {
-            if !self.next_token() {
-                self.run = false;
-                break
+    fn parse_type(&mut self) -> Result<VarTypes, Diagnostic> {
+        // `int`, `[int]` or `[[int]]`, leaves the current token on the last token of the type
+        if self.current_token.token_type == TokenType::BracketOpen {
+            self.next_token();
+            let element_type = self.parse_type()?;
+            if !self.next_token() || self.current_token.token_type != TokenType::BracketClose {
+                return Err(self.error(format!("Expected ']' got '{:?}' instead", &self.current_token.token_type)))
+            }
+            return Ok(VarTypes::Array(Box::new(element_type)))
+        }
+        if self.current_token.token_type != TokenType::Identifier {
+            return Err(self.error(format!("Invalid Type `{}`", self.current_token.value)))
+        }
+        // any other name is a struct, the checker makes sure it exists
+        Ok(str_to_types(self.current_token.value.clone()).unwrap_or(VarTypes::Struct(self.current_token.value.clone())))
+    }
+    fn struct_fields(&mut self) -> Result<Vec<(Token, VarTypes)>, Diagnostic> {
+        // { <type> <field>; ... }, leaves the current token on the closing bracket
+        let mut fields = vec![];
+        loop {
+            self.next_token();
+            if self.current_token.token_type == TokenType::CurlyBracketClose {
+                return Ok(fields)
+            } else if self.current_token.token_type == TokenType::EndOfFile {
+                return Err(self.error("Expected '}' to close the struct".to_string()))
             }
-            if self.current_token.token_type == TokenType::Identifier && variable_types.contains(&self.current_token.value) {
-                let resulting_var_type = str_to_types(self.current_token.value.clone());
-                if resulting_var_type.is_none(){
-                    self.error(format!("Invalid Type `{}`", self.current_token.value))
+            let field_type = self.parse_type()?;
+            if !self.next_token() || self.current_token.token_type != TokenType::Identifier {
+                return Err(self.error(format!("Expected a field name got '{:?}' instead", &self.current_token.token_type)))
+            }
+            let field_name = self.current_token.clone();
+            if !self.next_token() || self.current_token.token_type != TokenType::EndLine {
+                return Err(self.error(format!("Expected {:?} got {:?}", TokenType::EndLine, self.current_token.token_type)))
+            }
+            fields.push((field_name, field_type));
+        }
+    }
+    fn impl_name(&self) -> Option<String> {
+        // the struct whose impl block is being parsed
+        match self.scope.last() {
+            Some(Parsed::Impl(struct_name, _)) => Some(struct_name.value.clone()),
+            _ => None
+        }
+    }
+    fn statement(&mut self) -> Result<(), Diagnostic> {
+        if self.impl_name().is_some() && !matches!(self.current_token.token_type, TokenType::Fun | TokenType::CurlyBracketClose | TokenType::EndOfFile) {
+            return Err(self.error("only functions can be defined in an impl block".to_string()))
+        }
+        // `int a`, `Point p` and `[int] a` all start a declaration
+        let next_is_name = self.get_next_token().is_some_and(|next| next.token_type == TokenType::Identifier);
+        if (self.current_token.token_type == TokenType::Identifier && (VARIABLE_TYPES.contains(&&*self.current_token.value) || next_is_name)) ||
+            self.current_token.token_type == TokenType::BracketOpen {
+            let variable_type = self.parse_type()?;
+
+            if !self.next_token() || self.current_token.token_type != TokenType::Identifier {
+                return Err(self.error(format!("Expected a variable name got '{:?}' instead", &self.current_token.token_type)))
+            }
+            let variable_name = self.current_token.clone();
+
+            if !self.next_token() || self.current_token.token_type != TokenType::AssignmentArrow {
+                return Err(self.error(format!("Expected a variable assignment operator '<-' got '{:?}' instead", &self.current_token.token_type)))
+            }
+
+            let mut variable_values_for_evaluation = vec![];
+            loop {
+                self.next_token();
+                if self.current_token.token_type == TokenType::EndLine {
+                    break
+                } else if self.current_token.token_type == TokenType::EndOfFile {
+                    return Err(self.error(format!("Expected end of line got '{:?}' instead", &self.current_token.token_type)))
+                } else if self.current_token.is_data_type() || ALLOWED_TOKENS_IN_EVALUATION.contains(&self.current_token.token_type) {
+                    variable_values_for_evaluation.push(self.current_token.clone())
+                } else {
+                    return Err(self.error(format!("Expected Values got {:?}", self.current_token.token_type)))
                 }
-                let variable_type = resulting_var_type.unwrap();
 
-                if !self.next_token() || self.current_token.token_type != TokenType::Identifier {
-                    self.error(format!("Expected a variable name got '{:?}' instead", &self.current_token.token_type))
+            }
+            // if variable_values_for_evaluation.len() == 0 {
+            //     println!("WANING! uninitialized but declared {}", variable_name.value)
+            // }
+            self.add_var(variable_name, variable_type, variable_values_for_evaluation)?;
+        }
+        else if self.current_token.token_type == TokenType::If {
+            let if_position = (self.current_token.x, self.current_token.y);
+
+            let mut if_condition = vec![];
+            loop {
+                self.next_token();
+                if self.current_token.token_type == TokenType::EndOfFile {
+                    return Err(self.error("Expected Arguments".to_string()))
+                } else if self.current_token.token_type == TokenType::CurlyBracketOpen {
+                    break
+                } else {
+                    if_condition.push(self.current_token.clone())
                 }
-                let variable_name = self.current_token.clone();
+            }
+            self.add_if(if_condition, if_position)?;
+        }
+        else if self.current_token.token_type == TokenType::While {
+            let while_position = (self.current_token.x, self.current_token.y);
 
-                if !self.next_token() || self.current_token.token_type != TokenType::AssignmentArrow {
-                    self.error(format!("Expected a variable assignment operator '<-' got '{:?}' instead", &self.current_token.token_type))
+            let mut while_condition = vec![];
+            loop {
+                self.next_token();
+                if self.current_token.token_type == TokenType::EndOfFile {
+                    return Err(self.error("Expected Arguments".to_string()))
+                } else if self.current_token.token_type == TokenType::CurlyBracketOpen {
+                    break
+                } else {
+                    while_condition.push(self.current_token.clone())
                 }
+            }
+            self.add_while(while_condition, while_position)?;
+        }
+        else if self.current_token.token_type == TokenType::For {
+            if !self.next_token() || self.current_token.token_type != TokenType::Identifier {
+                return Err(self.error(format!("Expected a variable name got '{:?}' instead", &self.current_token.token_type)))
+            }
+            let loop_variable = self.current_token.clone();
+            if !self.next_token() || self.current_token.token_type != TokenType::In {
+                return Err(self.error(format!("Expected 'in' got '{:?}' instead", &self.current_token.token_type)))
+            }
 
-                let mut variable_values_for_evaluation = vec![];
-                loop {
-                    self.next_token();
-                    if self.current_token.token_type == TokenType::EndLine {
-                        break
-                    } else if self.current_token.token_type == TokenType::EndOfFile {
-                        self.error(format!("Expected end of line got '{:?}' instead", &self.current_token.token_type))
-                    } else if self.current_token.is_data_type() || allowed_tokens_in_evaluation.contains(&self.current_token.token_type) {
-                        variable_values_for_evaluation.push(self.current_token.clone())
-                    } else {
-                        self.error(format!("Expected Values got {:?}", self.current_token.token_type))
+            // for <variable> in <start>..<end> [step <step>] {
+            let mut range_start = vec![];
+            let mut range_end = vec![];
+            let mut range_step = vec![];
+            let mut current_part = 0;
+            loop {
+                self.next_token();
+                if self.current_token.token_type == TokenType::EndOfFile {
+                    return Err(self.error("Expected start of scope".to_string()))
+                } else if self.current_token.token_type == TokenType::CurlyBracketOpen {
+                    break
+                } else if self.current_token.token_type == TokenType::Range && current_part == 0 {
+                    current_part = 1;
+                } else if self.current_token.token_type == TokenType::Step && current_part == 1 {
+                    current_part = 2;
+                } else if self.current_token.is_data_type() || ALLOWED_TOKENS_IN_EVALUATION.contains(&self.current_token.token_type) {
+                    match current_part {
+                        0 => range_start.push(self.current_token.clone()),
+                        1 => range_end.push(self.current_token.clone()),
+                        _ => range_step.push(self.current_token.clone())
                     }
-
+                } else {
+                    return Err(self.error(format!("Expected Values got {:?}", self.current_token.token_type)))
                 }
-                // if variable_values_for_evaluation.len() == 0 {
-                //     println!("WANING! uninitialized but declared {}", variable_name.value)
-                // }
-                self.add_var(variable_name, variable_type, variable_values_for_evaluation)
             }
-            else if self.current_token.token_type == TokenType::If {
-                let if_position = (self.current_token.x, self.current_token.y);
+            if range_start.is_empty() || range_end.is_empty() || (current_part == 2 && range_step.is_empty()) {
+                return Err(self.error("Expected a range like `0..10` or `0..10 step 2`".to_string()))
+            }
+            self.add_for(loop_variable, range_start, range_end, range_step)?;
+        }
+        else if self.current_token.token_type == TokenType::Break || self.current_token.token_type == TokenType::Continue {
+            let keyword = self.current_token.clone();
+            if !self.next_token() || self.current_token.token_type != TokenType::EndLine {
+                return Err(self.error(format!("Expected {:?} got {:?}", TokenType::EndLine,  self.current_token.token_type)))
+            }
+            if keyword.token_type == TokenType::Break {
+                self.add_to_top_of_stack(Parsed::Break(keyword))
+            } else {
+                self.add_to_top_of_stack(Parsed::Continue(keyword))
+            }
+        }
+        else if self.current_token.token_type == TokenType::Else {
+            self.next_token();
+            if self.current_token.token_type == TokenType::If {
+                let else_if_pos = (self.current_token.x, self.current_token.y);
 
-                let mut if_condition = vec![];
+                let mut else_if_condition = vec![];
                 loop {
                     self.next_token();
                     if self.current_token.token_type == TokenType::EndOfFile {
-                        self.error("Expected Arguments".to_string());
+                        return Err(self.error("Expected Arguments".to_string()))
                     } else if self.current_token.token_type == TokenType::CurlyBracketOpen {
                         break
                     } else {
-                        if_condition.push(self.current_token.clone())
+                        else_if_condition.push(self.current_token.clone())
                     }
                 }
-                self.add_if(if_condition, if_position);
+                self.add_else(else_if_condition, else_if_pos)?;
+            } else {
+                let else_pos = (self.current_token.x, self.current_token.y);
+                if self.current_token.token_type != TokenType::CurlyBracketOpen {
+                    return Err(self.error(format!("Expected start of scope got '{:?}' instead", &self.current_token.token_type)))
+                }
+                let mut always = Token::new(TokenType::Boolean, "true".to_string());
+                always.set_xy(else_pos.0, else_pos.1);
+                self.add_else(vec![always], else_pos)?;
             }
-            else if self.current_token.token_type == TokenType::Else {
-                self.next_token();
-                if self.current_token.token_type == TokenType::If {
-                    let else_if_pos = (self.current_token.x, self.current_token.y);
 
-                    let mut else_if_condition = vec![];
-                    loop {
-                        self.next_token();
-                        if self.current_token.token_type == TokenType::EndOfFile {
-                            self.error("Expected Arguments".to_string());
-                        } else if self.current_token.token_type == TokenType::CurlyBracketOpen {
-                            break
-                        } else {
-                            else_if_condition.push(self.current_token.clone())
-                        }
-                    }
-                    self.add_else(else_if_condition, else_if_pos);
-                } else {
-                    let else_pos = (self.current_token.x, self.current_token.y);
-                    if self.current_token.token_type != TokenType::CurlyBracketOpen {
-                        self.error(format!("Expected start of scope got '{:?}' instead", &self.current_token.token_type))
+        }
+        else if self.current_token.token_type == TokenType::Fun {
+            let impl_name = self.impl_name();
+            if self.scope.len() != 1 && impl_name.is_none() {
+                return Err(self.error("Functions can only be defined at the top level".to_string()))
+            }
+            if !self.next_token() || self.current_token.token_type != TokenType::Identifier {
+                return Err(self.error(format!("Expected a function name got '{:?}' instead", &self.current_token.token_type)))
+            }
+            let function_name = self.current_token.clone();
+
+            if !self.next_token() || self.current_token.token_type != TokenType::ParenthesisOpen {
+                return Err(self.error(format!("Expected '(' got '{:?}' instead", &self.current_token.token_type)))
+            }
+            let mut function_arguments = vec![];
+            loop {
+                self.next_token();
+                if self.current_token.token_type == TokenType::ParenthesisClose && function_arguments.is_empty() {
+                    break
+                }
+                if self.current_token.value == "self" && self.current_token.token_type == TokenType::Identifier && function_arguments.is_empty() {
+                    // methods take the struct they are called on first, its type is the one of the impl block
+                    let Some(struct_name) = &impl_name else {
+                        return Err(self.error("'self' can only be used by functions in an impl block".to_string()))
+                    };
+                    function_arguments.push((self.current_token.clone(), VarTypes::Struct(struct_name.clone())));
+                    self.next_token();
+                    if self.current_token.token_type == TokenType::ParenthesisClose {
+                        break
+                    } else if self.current_token.token_type != TokenType::SeperatorComma {
+                        return Err(self.error(format!("Expected ',' or ')' got '{:?}' instead", &self.current_token.token_type)))
                     }
-                    self.add_else(vec![Token::new(TokenType::Boolean, "true".to_string())], else_pos);
+                    continue
                 }
+                if self.current_token.token_type != TokenType::Identifier && self.current_token.token_type != TokenType::BracketOpen {
+                    return Err(self.error(format!("Expected an argument type got '{:?}' instead", &self.current_token.token_type)))
+                }
+                let argument_type = self.parse_type()?;
+                if !self.next_token() || self.current_token.token_type != TokenType::Identifier {
+                    return Err(self.error(format!("Expected an argument name got '{:?}' instead", &self.current_token.token_type)))
+                }
+                if self.current_token.value == "self" {
+                    return Err(self.error("'self' can't be given a type".to_string())
+                        .with_note("methods take a bare `self` first, it has the type of the impl block's struct".to_string()))
+                }
+                function_arguments.push((self.current_token.clone(), argument_type));
 
+                self.next_token();
+                if self.current_token.token_type == TokenType::ParenthesisClose {
+                    break
+                } else if self.current_token.token_type != TokenType::SeperatorComma {
+                    return Err(self.error(format!("Expected ',' or ')' got '{:?}' instead", &self.current_token.token_type)))
+                }
             }
-            else if self.current_token.token_type == TokenType::Fun { unimplemented!() }
-            else if self.current_token.token_type == TokenType::Identifier {
-                let identifier_name = self.current_token.clone();
-                if self.next_token() && self.current_token.token_type == TokenType::ParenthesisOpen {
-                    let mut function_args = vec![];
-                    let mut function_calls = 0;
-                    loop {
-                        self.next_token();
-                        if self.current_token.token_type == TokenType::ParenthesisClose && function_calls == 0 {
-                            break
-                        } else if self.current_token.token_type == TokenType::ParenthesisClose {
-                            function_args.push(self.current_token.clone());
-                            function_calls -= 1
-                        } else if self.current_token.token_type == TokenType::ParenthesisOpen {
-                            function_calls += 1;
-                            function_args.push(self.current_token.clone());
-                            unimplemented!()
-                        } else if self.current_token.is_data_type() || allowed_tokens_in_evaluation.contains(&self.current_token.token_type) ||
-                            self.current_token.token_type == TokenType::SeperatorComma {
-                            function_args.push(self.current_token.clone());
-                        } else {
-                            self.error(format!("Expected arguments got {:?}", self.current_token.token_type))
-                        }
+
+            // the return type can be left out for functions that don't return anything
+            let mut return_type = vec![];
+            self.next_token();
+            if self.current_token.token_type == TokenType::Identifier || self.current_token.token_type == TokenType::BracketOpen {
+                return_type.push(self.parse_type()?);
+                self.next_token();
+            }
+            if self.current_token.token_type != TokenType::CurlyBracketOpen {
+                return Err(self.error(format!("Expected start of scope got '{:?}' instead", &self.current_token.token_type)))
+            }
+            self.add_function(function_name, function_arguments, return_type);
+        }
+        else if self.current_token.token_type == TokenType::Impl {
+            if self.scope.len() != 1 {
+                return Err(self.error("Impl blocks can only be at the top level".to_string()))
+            }
+            if !self.next_token() || self.current_token.token_type != TokenType::Identifier {
+                return Err(self.error(format!("Expected a struct name got '{:?}' instead", &self.current_token.token_type)))
+            }
+            let struct_name = self.current_token.clone();
+            if !self.next_token() || self.current_token.token_type != TokenType::CurlyBracketOpen {
+                return Err(self.error(format!("Expected start of scope got '{:?}' instead", &self.current_token.token_type)))
+            }
+            self.scope.push(Parsed::Impl(struct_name, vec![]));
+        }
+        else if self.current_token.token_type == TokenType::Struct {
+            let struct_keyword = self.current_token.clone();
+            if !self.next_token() || self.current_token.token_type != TokenType::Identifier {
+                return Err(self.error(format!("Expected a struct name got '{:?}' instead", &self.current_token.token_type)))
+            }
+            let struct_name = self.current_token.clone();
+            if !self.next_token() || self.current_token.token_type != TokenType::CurlyBracketOpen {
+                return Err(self.error(format!("Expected start of scope got '{:?}' instead", &self.current_token.token_type)))
+            }
+
+            let fields = match self.struct_fields() {
+                Ok(fields) => fields,
+                Err(error) => {
+                    // skips to the end of the struct so its fields aren't parsed as statements
+                    while !matches!(self.current_token.token_type, TokenType::CurlyBracketClose | TokenType::EndOfFile) && self.next_token() {}
+                    return Err(error)
+                }
+            };
+            // reported after the whole struct was read, so its fields aren't parsed as statements
+            if self.scope.len() != 1 {
+                self.errors.push(Diagnostic::at_token(ErrorKind::Syntax, "Structs can only be defined at the top level".to_string(), &struct_keyword));
+                return Ok(())
+            }
+            self.add_to_top_of_stack(Parsed::StructDefinition(struct_name, fields));
+        }
+        else if self.current_token.token_type == TokenType::Return {
+            let return_token = self.current_token.clone();
+            let mut return_values = vec![];
+            loop {
+                self.next_token();
+                if self.current_token.token_type == TokenType::EndLine {
+                    break
+                } else if self.current_token.token_type == TokenType::EndOfFile {
+                    return Err(self.error(format!("Expected end of line got '{:?}' instead", &self.current_token.token_type)))
+                } else if self.current_token.is_data_type() || ALLOWED_TOKENS_IN_EVALUATION.contains(&self.current_token.token_type) {
+                    return_values.push(self.current_token.clone())
+                } else {
+                    return Err(self.error(format!("Expected Values got {:?}", self.current_token.token_type)))
+                }
+            }
+            self.add_return(return_token, return_values)?;
+        }
+        else if self.current_token.token_type == TokenType::Identifier {
+            let identifier_name = self.current_token.clone();
+            if self.next_token() && self.current_token.token_type == TokenType::ParenthesisOpen {
+                let mut function_args = vec![identifier_name, self.current_token.clone()];
+                let mut function_calls = 0;
+                loop {
+                    self.next_token();
+                    if self.current_token.token_type == TokenType::ParenthesisClose && function_calls == 0 {
+                        function_args.push(self.current_token.clone());
+                        break
+                    } else if self.current_token.token_type == TokenType::ParenthesisClose {
+                        function_args.push(self.current_token.clone());
+                        function_calls -= 1
+                    } else if self.current_token.token_type == TokenType::ParenthesisOpen {
+                        function_calls += 1;
+                        function_args.push(self.current_token.clone());
+                    } else if self.current_token.is_data_type() || ALLOWED_TOKENS_IN_EVALUATION.contains(&self.current_token.token_type) {
+                        function_args.push(self.current_token.clone());
+                    } else {
+                        return Err(self.error(format!("Expected arguments got {:?}", self.current_token.token_type)))
                     }
-                    if !self.next_token() || self.current_token.token_type != TokenType::EndLine {
-                        self.error(format!("Expected {:?} got {:?}", TokenType::EndLine,  self.current_token.token_type))
+                }
+                if !self.next_token() || self.current_token.token_type != TokenType::EndLine {
+                    return Err(self.error(format!("Expected {:?} got {:?}", TokenType::EndLine,  self.current_token.token_type)))
+                }
+                self.add_func_call(function_args)?;
+            }
+            else if self.current_token.token_type == TokenType::AssignmentArrow {
+                 let mut reassign_values = vec![];
+                loop {
+                    self.next_token();
+                    if self.current_token.token_type == TokenType::EndLine {
+                        break
+                    } else if self.current_token.token_type == TokenType::EndOfFile {
+                        return Err(self.error(format!("Expected end of line got '{:?}' instead", &self.current_token.token_type)))
+                    } else if self.current_token.is_data_type() || ALLOWED_TOKENS_IN_EVALUATION.contains(&self.current_token.token_type) {
+                        reassign_values.push(self.current_token.clone())
+                    } else {
+                        return Err(self.error(format!("Expected Values got {:?}", self.current_token.token_type)))
                     }
-                    self.add_func_call(identifier_name, function_args)
+
                 }
-                else if self.current_token.token_type == TokenType::AssignmentArrow {
-                     let mut reassign_values = vec![];
-                    loop {
-                        self.next_token();
-                        if self.current_token.token_type == TokenType::EndLine {
-                            break
-                        } else if self.current_token.token_type == TokenType::EndOfFile {
-                            self.error(format!("Expected end of line got '{:?}' instead", &self.current_token.token_type))
-                        } else if self.current_token.is_data_type() || allowed_tokens_in_evaluation.contains(&self.current_token.token_type) {
+                self.reassign_var(identifier_name, reassign_values)?;
+            }
+            else if self.current_token.token_type == TokenType::BracketOpen || self.current_token.token_type == TokenType::DirectMemberSelection {
+                // a[i] <- v or p.x <- v, everything before the arrow is the element being set
+                let mut target = vec![identifier_name];
+                let mut reassign_values = vec![];
+                let mut assigning = false;
+                loop {
+                    if self.current_token.token_type == TokenType::EndLine {
+                        break
+                    } else if self.current_token.token_type == TokenType::EndOfFile {
+                        return Err(self.error(format!("Expected end of line got '{:?}' instead", &self.current_token.token_type)))
+                    } else if self.current_token.token_type == TokenType::AssignmentArrow && !assigning {
+                        assigning = true
+                    } else if self.current_token.is_data_type() || ALLOWED_TOKENS_IN_EVALUATION.contains(&self.current_token.token_type) {
+                        if assigning {
                             reassign_values.push(self.current_token.clone())
                         } else {
-                            self.error(format!("Expected Values got {:?}", self.current_token.token_type))
+                            target.push(self.current_token.clone())
                         }
-
-                    }
-                    if reassign_values.len() == 0 {
-                        self.error("Expected values".to_string());
+                    } else {
+                        return Err(self.error(format!("Expected Values got {:?}", self.current_token.token_type)))
                     }
-                    self.reassign_var(identifier_name, reassign_values)
+                    self.next_token();
+                }
+                if !assigning {
+                    return self.add_method_call(target)
                 }
+                self.reassign_element(target, reassign_values)?;
             }
-            else if self.current_token.token_type == TokenType::CurlyBracketClose &&
-                self.get_next_token().unwrap().token_type != TokenType::Else {
+        }
+        else if self.current_token.token_type == TokenType::CurlyBracketClose &&
+            self.get_next_token().unwrap().token_type != TokenType::Else {
 
-                if self.scope.len() == 1 {
-                    self.error(format!("Unexpected `{}`", self.current_token.value))
-                }
-                self.un_scope();
+            if self.scope.len() == 1 {
+                return Err(self.error("Unexpected '}'".to_string()))
+            }
+            self.un_scope();
+        }
+        else if self.current_token.token_type == TokenType::EndOfFile ||
+            self.current_token.token_type == TokenType::CurlyBracketClose { }
+        else {
+            return Err(self.error(format!("Unexpected '{:?}'", self.current_token.token_type)))
+        }
+        Ok(())
+    }
+    fn synchronize(&mut self) {
+        // skips the rest of a statement with a syntax error so parsing can go on after it
+        loop {
+            match self.current_token.token_type {
+                TokenType::EndLine | TokenType::EndOfFile => return,
+                TokenType::CurlyBracketOpen => {
+                    // the body is still parsed so its closing bracket has a block to close
+                    self.scope.push(Parsed::Recovered(vec![], (self.current_token.x, self.current_token.y)));
+                    return
+                },
+                TokenType::CurlyBracketClose => {
+                    // leaves the bracket for the main loop to close the block with
+                    if self.scope.len() > 1 {
+                        self.index -= 1;
+                    }
+                    return
+                },
+                _ => {}
+            }
+            if !self.next_token() {
+                return
             }
-            else if self.current_token.token_type == TokenType::EndOfFile { }
-            else if self.current_token.token_type == TokenType::CurlyBracketClose {}
-            else {
-                self.error(format!("Unknown! {:?}", self.current_token))
+        }
+    }
+    fn parse(&mut self) -> Result<Parsed, Vec<Diagnostic>> {
+        while self.run {
+            if !self.next_token() {
+                self.run = false;
+                break
+            }
+            if let Err(error) = self.statement() {
+                self.errors.push(error);
+                self.synchronize();
             }
         }
         if self.scope.len() > 1 {
             let index = self.scope.len() - 1;
             let last_compound_statements_container = &self.scope[index];
-            let mut position = (0, 0);
-            match last_compound_statements_container {
+            let position = match last_compound_statements_container {
                 Parsed::Conditions(condition) => {
                     let index2 = condition.len();
-                    position = condition[index2 - 1].2.clone();
-
+                    condition[index2 - 1].2
                 }
+                Parsed::FunctionDefinition(name, ..) => (name.x, name.y),
+                Parsed::While(_, _, while_position) => *while_position,
+                Parsed::For(variable, ..) => (variable.x, variable.y),
+                Parsed::Recovered(_, recovered_position) => *recovered_position,
+                Parsed::Impl(name, _) => (name.x, name.y),
                 _ => unimplemented!()
-            }
-            panic!("unclosed Block, at line {} char {}", position.1, position.0)
+            };
+            self.errors.push(Diagnostic::new(ErrorKind::Syntax, "unclosed Block".to_string(), Span::new(position.1, position.0, 1))
+                .with_label("this block is never closed".to_string())
+                .with_secondary(Span::from_token(&self.current_token), "expected '}' before the end of the file".to_string()))
+        }
+        if !self.errors.is_empty() {
+            return Err(std::mem::take(&mut self.errors))
         }
-        self.scope[0].clone()
+        Ok(self.scope[0].clone())
     }
 }
\ No newline at end of file
@@ -0,0 +1,151 @@
+use std::io::{BufRead, IsTerminal, Write};
+use crate::engine::Engine;
+use crate::error::ErrorKind;
+use crate::interpreter::{Overflow, Value};
+use crate::sl;
+
+const HELP: &str = "type statements to run them or an expression to print its value
+
+commands:
+    :type <expression>   print the type of an expression
+    :vars                print every variable
+    :reset               forget every variable and function
+    :load <file>         run a file, what it defines stays around
+    :help                print this message
+    :quit                leave, ctrl-d works too";
+
+const REPL_NAME: &str = "<repl>";
+
+// every input is run by the same engine, so what it defines stays around like it does for `Engine::eval`
+pub struct Repl {
+    engine: Engine,
+    error_limit: usize,
+    // kept for :reset
+    overflow: Overflow,
+}
+
+fn show(value: &Value) -> String {
+    // strings get quotes so "1" and 1 look different
+    match value {
+        Value::String(text) => format!("{:?}", text),
+        value => value.to_string()
+    }
+}
+
+fn brace_depth(text: &str) -> i32 {
+    // how many blocks are still open, brackets in strings and comments don't count
+    // a string that isn't closed yet counts as open too, strings can go over several lines
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut raw = false;
+    let mut in_char = false;
+    let mut previous = ' ';
+    let mut characters = text.chars().peekable();
+    while let Some(character) = characters.next() {
+        match character {
+            // skips what is escaped so `"\""` doesn't end the string early
+            '\\' if (in_string && !raw) || in_char => { characters.next(); },
+            '"' if !in_char => {
+                raw = !in_string && previous == 'r';
+                in_string = !in_string;
+            },
+            // `'{'` doesn't open a block
+            '\'' if !in_string => in_char = !in_char,
+            _ if in_string || in_char => {},
+            '/' if characters.peek() == Some(&'/') => {
+                while characters.next_if(|next| *next != '\n').is_some() {}
+            },
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => {}
+        }
+        previous = character;
+    }
+    if in_string { depth.max(0) + 1 } else { depth }
+}
+
+impl Repl {
+    pub fn new(error_limit: usize, overflow: Overflow) -> Self {
+        let mut engine = Engine::new();
+        engine.set_overflow(overflow);
+        Self {
+            engine,
+            error_limit,
+            overflow
+        }
+    }
+    fn run_input(&mut self, name: &str, source: &str) {
+        // a failed input leaves no trace, only what it printed stays
+        match self.engine.run(source) {
+            Ok(Value::Void) => {},
+            Ok(value) => println!("{}", show(&value)),
+            // `exit` in the repl only stops the input it is in
+            Err(errors) if errors.iter().all(|error| matches!(error.kind, ErrorKind::Exit(_))) => {},
+            Err(errors) => sl::report(errors, name, source, self.error_limit)
+        }
+    }
+    fn type_command(&mut self, source: &str) {
+        match self.engine.type_of(source) {
+            Ok(Some(found_type)) => println!("{:?}", found_type),
+            Ok(None) => println!("no value"),
+            Err(error) => sl::report(vec![error], REPL_NAME, source, self.error_limit)
+        }
+    }
+    fn command(&mut self, line: &str) -> bool {
+        // returns false when the repl should stop
+        let (command, argument) = line.split_once(' ').unwrap_or((line, ""));
+        let argument = argument.trim();
+        match command {
+            ":type" => self.type_command(argument),
+            ":vars" => {
+                for (name, var_type, value) in self.engine.globals() {
+                    println!("{}: {:?} = {}", name, var_type, show(&value))
+                }
+            },
+            ":reset" => *self = Self::new(self.error_limit, self.overflow),
+            ":load" => match sl::read_source(argument) {
+                Ok(source) => self.run_input(argument, &source),
+                Err(errors) => sl::report(errors, argument, "", self.error_limit)
+            },
+            ":help" => println!("{}", HELP),
+            ":quit" | ":q" => return false,
+            _ => eprintln!("unknown command '{}', see :help", command)
+        }
+        true
+    }
+    pub fn run(&mut self) {
+        let interactive = std::io::stdin().is_terminal();
+        let prompt = |text: &str| {
+            if interactive {
+                print!("{}", text);
+                let _ = std::io::stdout().flush();
+            }
+        };
+
+        let mut input = String::new();
+        prompt("> ");
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if input.is_empty() && line.trim_start().starts_with(':') {
+                if !self.command(line.trim()) {
+                    return
+                }
+                prompt("> ");
+                continue
+            }
+            input += &line;
+            input += "\n";
+            // blocks can go over several lines, the input ends once they are all closed
+            if brace_depth(&input) > 0 {
+                prompt(". ");
+                continue
+            }
+            if !input.trim().is_empty() {
+                let source = std::mem::take(&mut input);
+                self.run_input(REPL_NAME, &source);
+            }
+            input.clear();
+            prompt("> ");
+        }
+    }
+}
@@ -1,16 +1,36 @@
-use std::collections::HashMap;
-use crate::{Parsed, parser};
-use crate::check::{Checker, ArgTypes};
-use crate::interpreter::{Interpreter, Value};
-use crate::lexer::{Lexer, Token,TokenType };
-use crate::parser::VarTypes;
+use crate::parser::{self, Parsed};
+use crate::check::Checker;
+use crate::error::{Diagnostic, ErrorKind, Span};
+use crate::interpreter::{Interpreter, Overflow};
+use crate::native::Natives;
+use crate::lexer::{Lexer, Token};
 
+// how many errors are shown before the rest are only counted
+pub const ERROR_LIMIT: usize = 20;
 
+// `-` reads the script from stdin
+pub const STDIN_PATH: &str = "-";
 
-fn pre_compile(file_path: String, debug: bool) -> Parsed {
-    let file_content = std::fs::read_to_string(file_path).expect("couldnt open file");
+
+pub fn read_source(file_path: &str) -> Result<String, Vec<Diagnostic>> {
+    let source = if file_path == STDIN_PATH {
+        std::io::read_to_string(std::io::stdin())
+    } else {
+        std::fs::read_to_string(file_path)
+    };
+    source.map_err(|error| {
+        let shown_path = if file_path == STDIN_PATH { "stdin" } else { file_path };
+        vec![Diagnostic::new(ErrorKind::Io, format!("couldn't read '{}': {}", shown_path, error), Span::new(0, 0, 0))]
+    })
+}
+
+pub fn tokens(source: String) -> Result<Vec<Token>, Vec<Diagnostic>> {
+    Lexer::new().lex_text(source).map_err(|error| vec![error])
+}
+
+pub fn parse(source: String, debug: bool) -> Result<Parsed, Vec<Diagnostic>> {
     let mut the_parser = parser::Parser::new(debug);
-    let return_parsed = the_parser.parse_text(file_content);
+    let return_parsed = the_parser.parse_text(source)?;
     if debug {
         if let Parsed::Program(parsed) = &return_parsed {
             for part in parsed {
@@ -19,19 +39,49 @@ fn pre_compile(file_path: String, debug: bool) -> Parsed {
         }
         println!("--------------------------------------------------------");
     }
-    let mut type_checker = Checker::new();
-    type_checker.check_program(return_parsed.clone());
-    return_parsed
+    Ok(return_parsed)
+}
+
+pub fn pre_compile(source: String, debug: bool) -> Result<Parsed, Vec<Diagnostic>> {
+    let return_parsed = parse(source, debug)?;
+    let mut type_checker = Checker::new(&Natives::standard());
+    type_checker.check_program(return_parsed.clone())?;
+    Ok(return_parsed)
 }
 
+pub fn run(source: String, debug: bool, arguments: Vec<String>, overflow: Overflow) -> Result<i32, Vec<Diagnostic>> {
+    // returns the exit status of the script
+    let ret = pre_compile(source, debug)?;
 
-pub fn compile(file_path: String) {
-    unimplemented!()
+    let mut master = Interpreter::new(ret, Natives::standard());
+    master.set_arguments(arguments);
+    master.set_overflow(overflow);
+    match master.run() {
+        Ok(()) => Ok(0),
+        Err(Diagnostic { kind: ErrorKind::Exit(code), .. }) => Ok(code),
+        Err(error) => Err(vec![error])
+    }
 }
 
-pub fn interpret(file_path: String, debug: bool) {
-    let ret = pre_compile(file_path, debug);
+pub fn compile(source: String) -> Result<(), Vec<Diagnostic>> {
+    pre_compile(source, false)?;
+    Err(vec![Diagnostic::new(ErrorKind::Unsupported, "compiling isn't supported yet, use `run` instead".to_string(), Span::new(0, 0, 0))])
+}
 
-    let mut master = Interpreter::new(ret);
-    master.run();
-}
\ No newline at end of file
+pub fn report(errors: Vec<Diagnostic>, file_path: &str, source: &str, error_limit: usize) {
+    let shown_path = if file_path == STDIN_PATH { "<stdin>" } else { file_path };
+    for (index, error) in errors.iter().take(error_limit).enumerate() {
+        if index != 0 {
+            eprintln!()
+        }
+        let mut error = error.clone();
+        error.set_file(shown_path.to_string());
+        error.raise(source);
+    }
+    if errors.len() > error_limit {
+        eprintln!("\n... and {} more errors", errors.len() - error_limit);
+    }
+    if errors.len() > 1 {
+        eprintln!("\nstopped because of {} errors", errors.len());
+    }
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::{engine, error, run};
+use sl::{ErrorKind, Value};
+
+#[test]
+fn literals_and_indexing() {
+    assert_eq!(run(r#"
+        [int] numbers <- [1, 2, 3];
+        [[str]] grid <- [["a", "b"], ["c"]];
+        numbers[1] <- 20;
+        grid[1][0] <- "z";
+        out(numbers[0] + numbers[1]);
+        out(grid[1][0]);
+        out(len(grid));
+        out(grid);
+    "#), "21\nz\n2\n[[\"a\", \"b\"], [\"z\"]]\n");
+}
+
+#[test]
+fn arrays_are_copied() {
+    assert_eq!(run(r#"
+        fun change([int] values) [int] {
+            values[0] <- 9;
+            return values;
+        }
+        [int] first <- [1, 2];
+        [int] second <- first;
+        second[1] <- 5;
+        [int] third <- change(first);
+        out(first);
+        out(second);
+        out(third);
+    "#), "[1, 2]\n[1, 5]\n[9, 2]\n");
+}
+
+#[test]
+fn indexing_what_a_call_gives() {
+    assert_eq!(run("fun make() [[int]] { return [[1, 2], [3]]; } out(make()[0][1]); out([4, 5][1]);"), "2\n5\n");
+}
+
+#[test]
+fn reading_elements_of_elements() {
+    let (mut engine, _) = engine();
+    engine.set_global("big", Value::Array((0..1000).map(Value::Int).collect())).unwrap();
+    assert_eq!(engine.eval("int total <- 0; for i in 0..len(big) { total <- total + big[i]; } total"), Ok(Value::Int(499500)));
+    assert_eq!(engine.eval("[[int]] pairs <- [[1, 2], [3, 4]]; int i <- 1; pairs[i][i - 1] + pairs[0][i]"), Ok(Value::Int(5)));
+}
+
+#[test]
+fn empty_arrays_take_the_declared_type() {
+    assert_eq!(run("[float] nothing_yet <- []; out(len(nothing_yet));"), "0\n");
+    assert_eq!(error("[int] mixed <- [1, \"two\"];").kind, ErrorKind::Type);
+}
+
+#[test]
+fn index_out_of_bounds() {
+    let found = error("[int] numbers <- [1, 2, 3];\nint i <- 3;\nout(numbers[i]);");
+    assert_eq!(found.kind, ErrorKind::Runtime);
+    assert_eq!(found.span.line, 3);
+    assert_eq!(error("[int] numbers <- [1]; numbers[-1] <- 2;").kind, ErrorKind::Runtime);
+}
+
+#[test]
+fn array_types_are_checked() {
+    assert_eq!(error("[int] a <- [1]; out(a[\"x\"]);").kind, ErrorKind::Type);
+    assert_eq!(error("[int] a <- [1]; a[0] <- \"s\";").kind, ErrorKind::Type);
+    assert_eq!(error("int a <- 1; out(a[0]);").kind, ErrorKind::Type);
+    assert_eq!(error("fun f([int] a) { } f([1.0]);").kind, ErrorKind::Type);
+    assert_eq!(error("out([] == []);").kind, ErrorKind::Type);
+    assert_eq!(run("[[int]] grid <- [[1], []]; out(grid); out(grid[1] == []);"), "[[1], []]\ntrue\n");
+}
+
+#[test]
+fn arrays_are_compared_by_elements() {
+    assert_eq!(run("out([1, 2] == [1, 2]); out([1] != [2]); out([[1]] == [[1, 2]]);"), "true\ntrue\nfalse\n");
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::{engine, error, run};
+use sl::{BigInt, ErrorKind, Value};
+
+#[test]
+fn bigint_arithmetic() {
+    let output = run(r#"
+        bigint total <- 1;
+        for i in 1..31 {
+            total <- total * (i as bigint);
+        }
+        out(total);
+        out(total / 1000000007 % 1000);
+        out(-total + total);
+        bigint two <- 2;
+        out(two ** 200);
+        out((two ** 200) as int);
+    "#);
+    assert_eq!(output, "\
+265252859812191058636308480000000
+361
+0
+1606938044258990275541962092341162602522202993782792835301376
+0
+");
+
+    let (mut engine, _) = engine();
+    assert_eq!(engine.eval("bigint a <- 170141183460469231731687303715884105728; a - 1"),
+        Ok(Value::BigInt(BigInt::from(i128::MAX))));
+    let error = engine.eval("bigint b <- 2; b ** 4000000000").unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Runtime);
+    // a power of two is as large as allowed with 2^20 bits
+    assert_eq!(engine.eval("bigint two <- 2; (two ** 1000000) % 1000000007"), Ok(Value::BigInt(BigInt::from(235042059))));
+    assert_eq!(engine.eval("(two ** 1048575) % 7"), Ok(Value::BigInt(BigInt::from(1))));
+    assert_eq!(engine.eval("two ** 1048576").unwrap_err().kind, ErrorKind::Runtime);
+    assert_eq!(engine.eval("bigint three <- -3; (three ** 1001) % 1000"), Ok(Value::BigInt(BigInt::from(-3))));
+    let error = engine.eval("bigint c <- 0; 1 / c").unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Runtime);
+}
+
+#[test]
+fn bigint_keeps_its_sign() {
+    let a = "bigint a <- 100000000000000000000000000000000000000000;";
+    assert_eq!(run(&format!("{} out(-a / 7); out(-a % 7); out(a - a * 2);", a)),
+        "-14285714285714285714285714285714285714285\n-5\n-100000000000000000000000000000000000000000\n");
+    assert_eq!(run(&format!("{} out(a > 99999999999999999999999999999999999999999); out(a == a + 0);", a)), "true\ntrue\n");
+    assert_eq!(run("out(-170141183460469231731687303715884105729);"), "-170141183460469231731687303715884105729\n");
+}
+
+#[test]
+fn bigint_casts() {
+    assert_eq!(run("bigint a <- 340282366920938463463374607431768211457; out(a as int); out(a as u8);"), "1\n1\n");
+    assert_eq!(run("float f <- 100000000000000000000.0 * 100000000000000000000.0; out(f as bigint); out(-2.9 as bigint);"),
+        "10000000000000000303786028427003666890752\n-2\n");
+    assert_eq!(error("float f <- 1.0 / 0.0; out(f as bigint);").kind, ErrorKind::Runtime);
+    assert_eq!(error("bigint a <- 1; int b <- 1; out(a + b);").kind, ErrorKind::Type);
+}
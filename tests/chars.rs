@@ -0,0 +1,46 @@
+mod common;
+
+use common::{error, run};
+use sl::ErrorKind;
+
+#[test]
+fn strings_are_indexed_by_char() {
+    let output = run(r#"
+        str word <- "héllo wörld";
+        out(len(word));
+        int vowels <- 0;
+        for i in 0..len(word) {
+            char letter <- word[i];
+            if letter == 'é' or letter == 'ö' or letter == 'o' {
+                vowels <- vowels + 1;
+            }
+        }
+        out(vowels);
+        out(word[10]);
+        word <- "ab";
+        out(word[1]);
+        [str] words <- ["añb"];
+        out(words[0][1]);
+    "#);
+    assert_eq!(output, "11\n3\nd\nb\nñ\n");
+    assert_eq!(error(r#"str word <- "é"; word[1]"#).kind, ErrorKind::Runtime);
+    assert_eq!(error("len(1)").kind, ErrorKind::Type);
+}
+
+#[test]
+fn chars_compare_and_cast() {
+    assert_eq!(run("char letter <- 'a'; out(letter < 'b'); out(letter as int); out((letter as int + 1) as char); out('é' > 'z');"),
+        "true\n97\nb\ntrue\n");
+    assert_eq!(run("out('\\u{1F600}'); out('a' == 'a'); out('a' != 'b'); [char] cs <- ['a', 'b']; out(cs);"), "😀\ntrue\ntrue\n['a', 'b']\n");
+    assert_eq!(run("fun next(char c) char { return (c as int + 1) as char; } out(next('y'));"), "z\n");
+}
+
+#[test]
+fn char_errors() {
+    assert_eq!(error("char c <- \"a\";").kind, ErrorKind::Type);
+    assert_eq!(error("char c <- 'a'; out(c + 'b');").kind, ErrorKind::Type);
+    assert_eq!(error("out('a' as float);").kind, ErrorKind::Type);
+    assert_eq!(error("str word <- \"ab\"; word[0] <- 'c';").kind, ErrorKind::Type);
+    assert_eq!(error("out(-1 as char);").kind, ErrorKind::Runtime);
+    assert_eq!(error("out(1114112 as char);").kind, ErrorKind::Runtime);
+}
@@ -0,0 +1,138 @@
+mod common;
+
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use common::{engine, sl};
+use sl::{ErrorKind, Value};
+
+// a script in the temp directory, named after the test so tests running at once don't share one
+fn script(name: &str, source: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("sl-cli-{}-{}.sl", name, std::process::id()));
+    std::fs::write(&path, source).unwrap();
+    path
+}
+
+#[test]
+fn a_reader_that_stops_early_is_fine() {
+    // far more output than a pipe holds, so the writes after the reader is gone fail
+    let path = script("early", &"int a <- 1 + 2;\n".repeat(20000));
+    for command in ["tokens", "ast"] {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_SL"))
+            .args([command, path.to_str().unwrap()])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut first = String::new();
+        BufReader::new(child.stdout.take().unwrap()).read_line(&mut first).unwrap();
+        assert!(!first.is_empty());
+        let mut errors = String::new();
+        child.stderr.take().unwrap().read_to_string(&mut errors).unwrap();
+        assert!(child.wait().unwrap().success(), "{}", errors);
+        assert_eq!(errors, "");
+    }
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn run_passes_the_arguments_after_the_file() {
+    let script = "out(arg_count()); out(arg(0)); out(arg(1));";
+    assert_eq!(sl(&["run", "-", "a", "b c"], script), (0, "2\na\nb c\n".to_string(), String::new()));
+    // `--` lets the script get arguments that look like options
+    assert_eq!(sl(&["run", "-", "--", "--debug", "x"], script).1, "2\n--debug\nx\n");
+    let (code, _, errors) = sl(&["run", "-"], "out(arg(3));");
+    assert_eq!(code, 1);
+    assert!(errors.contains("there is no argument 3, 0 were given"), "{}", errors);
+}
+
+#[test]
+fn run_a_file() {
+    let path = script("file", "out(\"from a file\");");
+    assert_eq!(sl(&["run", path.to_str().unwrap()], ""), (0, "from a file\n".to_string(), String::new()));
+    std::fs::remove_file(path).unwrap();
+    let (code, _, errors) = sl(&["run", "/no/such/file.sl"], "");
+    assert_eq!(code, 1);
+    assert!(errors.starts_with("io error: couldn't read '/no/such/file.sl'"), "{}", errors);
+}
+
+#[test]
+fn exit_sets_the_status() {
+    assert_eq!(sl(&["run", "-"], "out(1); exit(7); out(2);"), (7, "1\n".to_string(), String::new()));
+    assert_eq!(sl(&["run", "-"], "exit(0);").0, 0);
+    assert_eq!(sl(&["run", "-"], "exit(256);").0, 1);
+}
+
+#[test]
+fn check_and_build_do_not_run() {
+    assert_eq!(sl(&["check", "-"], "out(1);"), (0, String::new(), String::new()));
+    let (code, output, errors) = sl(&["build", "-"], "out(1);");
+    assert_eq!((code, output.as_str()), (1, ""));
+    assert!(errors.starts_with("unsupported"), "{}", errors);
+}
+
+#[test]
+fn options() {
+    let script = "int a <- 170141183460469231731687303715884105727; out(a + 1);";
+    assert_eq!(sl(&["run", "--overflow", "wrapping", "-"], script).1, "-170141183460469231731687303715884105728\n");
+    assert_eq!(sl(&["run", "--overflow", "saturating", "-"], script).1, "170141183460469231731687303715884105727\n");
+    assert_eq!(sl(&["run", "-"], script).0, 1);
+    let (code, output, _) = sl(&["run", "--debug", "-"], "out(1);");
+    assert_eq!(code, 0);
+    assert!(output.contains("Token") && output.ends_with("1\n"), "{}", output);
+}
+
+#[test]
+fn usage_errors() {
+    for arguments in [vec![], vec!["nope"], vec!["run"], vec!["run", "--nope", "-"], vec!["run", "--overflow", "odd", "-"],
+        vec!["check", "--error-limit", "0", "-"], vec!["repl", "file.sl"]] {
+        let (code, output, errors) = sl(&arguments, "");
+        assert_eq!((code, output.as_str()), (2, ""), "{:?}", arguments);
+        assert!(errors.ends_with("see `SL help`\n"), "{:?}: {}", arguments, errors);
+    }
+    let (code, output, _) = sl(&["help"], "");
+    assert_eq!(code, 0);
+    assert!(output.starts_with("usage: SL <command>"), "{}", output);
+}
+
+#[test]
+fn tokens_and_ast() {
+    let (code, output, _) = sl(&["tokens", "-"], "int a <- 1;");
+    assert_eq!(code, 0);
+    assert_eq!(output.lines().count(), 6, "{}", output);
+    assert!(output.starts_with("Token { token_type: Identifier, value: \"int\""), "{}", output);
+    let (code, output, _) = sl(&["ast", "-"], "int a <- 1;");
+    assert_eq!(code, 0);
+    assert!(output.starts_with("Program("), "{}", output);
+}
+
+#[test]
+fn exit_keeps_what_ran_before_it() {
+    let (mut engine, output) = engine();
+    let error = engine.eval(r#"
+        int kept <- 1;
+        fun stop(int code) {
+            int inner <- 2;
+            out("stopping");
+            exit(code);
+        }
+        stop(3);
+        out("not reached");
+    "#).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Exit(3));
+    assert_eq!(output.borrow().as_slice(), b"stopping\n");
+    assert_eq!(engine.get_global("kept"), Some(Value::Int(1)));
+    // the function's variables are gone, so the names can be used again
+    assert_eq!(engine.get_global("inner"), None);
+    assert_eq!(engine.eval("int inner <- 4; inner + kept"), Ok(Value::Int(5)));
+}
+
+#[test]
+fn exit_code_out_of_range() {
+    let (mut engine, _) = engine();
+    for code in ["256", "-1"] {
+        let error = engine.eval(&format!("exit({});", code)).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Runtime);
+    }
+}
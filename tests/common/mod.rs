@@ -0,0 +1,50 @@
+// helpers shared by the integration tests, every test file only uses some of them
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+
+use sl::{Diagnostic, Engine};
+
+// an engine whose `out` writes into the returned buffer
+pub fn engine() -> (Engine, Rc<RefCell<Vec<u8>>>) {
+    let mut engine = Engine::new();
+    let output = Rc::new(RefCell::new(Vec::new()));
+    engine.set_output(output.clone());
+    (engine, output)
+}
+
+// what the script wrote with `out`, panics with the error if it failed
+pub fn run(source: &str) -> String {
+    let (mut engine, output) = engine();
+    if let Err(error) = engine.eval(source) {
+        panic!("{}", error)
+    }
+    let written = output.borrow().clone();
+    String::from_utf8(written).unwrap()
+}
+
+// the error a script stops with, panics if it ran without one
+pub fn error(source: &str) -> Diagnostic {
+    match engine().0.eval(source) {
+        Ok(value) => panic!("expected an error, the script gave {:?}", value),
+        Err(error) => error
+    }
+}
+
+// runs the SL binary with the given stdin, gives its exit code, stdout and stderr
+pub fn sl(arguments: &[&str], input: &str) -> (i32, String, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_SL"))
+        .args(arguments)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    let code = output.status.code().expect("SL was stopped by a signal");
+    (code, String::from_utf8(output.stdout).unwrap(), String::from_utf8(output.stderr).unwrap())
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use common::{engine, error, sl};
+use sl::{Diagnostic, Emitter, ErrorKind, Span, Value};
+
+fn render(diagnostic: &Diagnostic, source: &str) -> String {
+    Emitter::new().with_color(false).render(diagnostic, source)
+}
+
+// the kind of the error a script stops with and where it points, as (line, column)
+fn kind_at(source: &str) -> (ErrorKind, (u32, u32)) {
+    let found = error(source);
+    (found.kind, (found.span.line, found.span.column))
+}
+
+#[test]
+fn errors_have_a_kind_and_a_position() {
+    assert_eq!(kind_at(r#"str a <- "abc"#), (ErrorKind::Syntax, (1, 10)));
+    assert_eq!(kind_at("char c <- 'ab';"), (ErrorKind::Syntax, (1, 11)));
+    assert_eq!(kind_at("@"), (ErrorKind::Syntax, (1, 1)));
+    assert_eq!(kind_at("int a <- 1 +;"), (ErrorKind::Syntax, (1, 12)));
+    assert_eq!(kind_at("fun f( { }"), (ErrorKind::Syntax, (1, 8)));
+    assert_eq!(kind_at("int a <- 1;\nout(nope);"), (ErrorKind::Name, (2, 5)));
+    assert_eq!(kind_at("int a <- \"x\";"), (ErrorKind::Type, (1, 5)));
+    assert_eq!(kind_at("if 1 { }"), (ErrorKind::Type, (1, 4)));
+    assert_eq!(kind_at("int z <- 0;\nout(1 / z);"), (ErrorKind::Runtime, (2, 7)));
+}
+
+#[test]
+fn errors_explain_themselves() {
+    let found = error("int a <- 1;\nint a <- 2;");
+    assert_eq!(found.message, "variable 'a' already exists");
+    assert_eq!(found.label.as_deref(), Some("declared again here"));
+    assert_eq!(found.secondary[0].span.line, 1);
+    assert_eq!(found.secondary[0].message, "first declared here");
+}
+
+#[test]
+fn rendering_with_the_source() {
+    let source = "int a <- 1;\nint b <- 2;\nint a <- 3;";
+    let mut found = error(source);
+    found.set_file("main.sl".to_string());
+    assert_eq!(render(&found, source), "\
+name error: variable 'a' already exists
+ --> main.sl:3:5
+  |
+1 | int a <- 1;
+  |     - first declared here
+...
+3 | int a <- 3;
+  |     ^ declared again here");
+    // without secondary labels only the line of the error is shown
+    assert_eq!(Emitter::new().with_color(false).with_secondary_labels(false).render(&found, source), "\
+name error: variable 'a' already exists
+ --> main.sl:3:5
+  |
+3 | int a <- 3;
+  |     ^ declared again here");
+}
+
+#[test]
+fn rendering_notes_and_wide_line_numbers() {
+    let source = format!("{}out(1 + \"a\");", "\n".repeat(11));
+    let diagnostic = Diagnostic::new(ErrorKind::Type, "can't add these".to_string(), Span::new(12, 9, 3))
+        .with_note("first note".to_string())
+        .with_note("second note".to_string());
+    assert_eq!(render(&diagnostic, &source), "\
+type error: can't add these
+  --> :12:9
+   |
+12 | out(1 + \"a\");
+   |         ^^^
+   |
+   = note: first note
+   = note: second note");
+}
+
+#[test]
+fn rendering_keeps_tabs() {
+    let source = "\tint a <- x;";
+    let diagnostic = Diagnostic::new(ErrorKind::Name, "no x".to_string(), Span::new(1, 11, 1)).with_label("here".to_string());
+    assert_eq!(render(&diagnostic, source), "name error: no x\n --> :1:11\n  |\n1 | \tint a <- x;\n  | \t         ^ here");
+}
+
+#[test]
+fn rendering_without_a_position() {
+    let mut diagnostic = Diagnostic::new(ErrorKind::Io, "couldn't read 'a.sl'".to_string(), Span::new(0, 0, 0));
+    diagnostic.set_file("a.sl".to_string());
+    assert_eq!(render(&diagnostic, ""), "io error: couldn't read 'a.sl'\n --> a.sl");
+    assert_eq!(diagnostic.to_string(), "io error: couldn't read 'a.sl'\n  --> a.sl");
+}
+
+#[test]
+fn rendering_in_color() {
+    let diagnostic = Diagnostic::new(ErrorKind::Runtime, "stop".to_string(), Span::new(1, 1, 1));
+    let colored = Emitter::new().with_color(true).render(&diagnostic, "x");
+    assert!(colored.starts_with("\x1b[1;31mruntime error\x1b[0m"), "{:?}", colored);
+    assert!(!render(&diagnostic, "x").contains('\x1b'));
+}
+
+#[test]
+fn every_error_is_counted() {
+    let (mut engine, _) = engine();
+    let error = engine.eval("int a <- true; str b <- 1; bool c <- 2;").unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Type);
+    assert!(error.notes.contains(&"2 more errors were found".to_string()), "{:?}", error.notes);
+    // nothing from the failed eval is kept
+    assert_eq!(engine.eval("int a <- 1; a"), Ok(Value::Int(1)));
+}
+
+#[test]
+fn syntax_errors_recover() {
+    let (mut engine, _) = engine();
+    let error = engine.eval("int a <- ; int b <- 2 int c <- 3;").unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Syntax);
+    assert_eq!(engine.eval("int a <- 1; a"), Ok(Value::Int(1)));
+}
+
+#[test]
+fn every_error_is_shown() {
+    let (code, output, errors) = sl(&["check", "-"], "int a <- true;\nstr b <- 1;\nout(a + 1);\nbool c <- 2;\n");
+    assert_eq!(code, 1);
+    assert_eq!(output, "");
+    // `a` is still declared as an int, so `a + 1` isn't an error
+    assert_eq!(errors.matches("type error").count(), 3, "{}", errors);
+    assert!(errors.ends_with("stopped because of 3 errors\n"), "{}", errors);
+
+    let (_, _, errors) = sl(&["check", "--error-limit", "1", "-"], "int a <- true;\nstr b <- 1;\nbool c <- 2;\n");
+    assert_eq!(errors.matches("type error").count(), 1, "{}", errors);
+    assert!(errors.contains("... and 2 more errors"), "{}", errors);
+}
+
+#[test]
+fn syntax_errors_skip_to_the_next_statement() {
+    let (code, _, errors) = sl(&["check", "-"], "int a <- ;\nfun f( { }\nint b <- 2 int c <- 3;\nout(b);\n");
+    assert_eq!(code, 1);
+    for line in ["<stdin>:1:", "<stdin>:2:", "<stdin>:3:"] {
+        assert!(errors.contains(line), "no error on {} in {}", line, errors);
+    }
+    assert!(!errors.contains("<stdin>:4:"), "{}", errors);
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::engine;
+use sl::{Engine, ErrorKind, IntType, Natives, Value, VarTypes};
+
+#[test]
+fn errors_roll_back() {
+    let (mut engine, output) = engine();
+    engine.eval("int a <- 1;").unwrap();
+    let error = engine.eval(r#"a <- 5; int b <- 2; out("ran"); b / 0"#).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Runtime);
+    assert_eq!(output.borrow().as_slice(), b"ran\n");
+    assert_eq!(engine.get_global("a"), Some(Value::Int(1)));
+    assert_eq!(engine.get_global("b"), None);
+    // `b` can be declared again since the failed eval never happened
+    assert_eq!(engine.eval("int b <- 3; a + b"), Ok(Value::Int(4)));
+}
+
+#[test]
+fn globals_have_to_match_their_struct() {
+    let (mut engine, _) = engine();
+    engine.eval("struct S { int x; [str] names; }").unwrap();
+    let s = |fields: Vec<(&str, Value)>| Value::Struct("S".to_string(), fields.into_iter().map(|(name, value)| (name.to_string(), value)).collect());
+
+    let unknown = Value::Struct("Nope".to_string(), vec![("x".to_string(), Value::Int(1))]);
+    assert_eq!(engine.set_global("p", unknown).unwrap_err().kind, ErrorKind::Type);
+    let renamed = s(vec![("y", Value::String("a".to_string())), ("names", Value::Array(vec![]))]);
+    assert_eq!(engine.set_global("s", renamed).unwrap_err().kind, ErrorKind::Type);
+    let wrong_type = s(vec![("x", Value::String("a".to_string())), ("names", Value::Array(vec![]))]);
+    assert_eq!(engine.set_global("s", wrong_type).unwrap_err().kind, ErrorKind::Type);
+    let missing = s(vec![("x", Value::Int(1))]);
+    assert_eq!(engine.set_global("s", missing).unwrap_err().kind, ErrorKind::Type);
+    let in_array = Value::Array(vec![s(vec![("x", Value::Bool(true)), ("names", Value::Array(vec![]))])]);
+    assert_eq!(engine.set_global("all", in_array).unwrap_err().kind, ErrorKind::Type);
+    assert_eq!(engine.get_global("s"), None);
+
+    let fine = s(vec![("x", Value::Int(1)), ("names", Value::Array(vec![Value::String("a".to_string())]))]);
+    engine.set_global("s", fine).unwrap();
+    assert_eq!(engine.eval("s.x + len(s.names)"), Ok(Value::Int(2)));
+}
+
+#[test]
+fn eval_keeps_what_it_defined() {
+    let mut engine = Engine::new();
+    assert_eq!(engine.eval("int a <- 2;"), Ok(Value::Void));
+    assert_eq!(engine.eval("fun twice(int x) int { return x * 2; }"), Ok(Value::Void));
+    assert_eq!(engine.eval("twice(a) + 1"), Ok(Value::Int(5)));
+    assert_eq!(engine.eval(r#""a" + "b""#), Ok(Value::String("ab".to_string())));
+    assert_eq!(engine.eval("[a, 3]"), Ok(Value::Array(vec![Value::Int(2), Value::Int(3)])));
+    // a clone goes on by itself
+    let mut copy = engine.clone();
+    copy.eval("a <- 10;").unwrap();
+    assert_eq!(engine.get_global("a"), Some(Value::Int(2)));
+    assert_eq!(copy.get_global("a"), Some(Value::Int(10)));
+}
+
+#[test]
+fn globals_from_the_host() {
+    let mut engine = Engine::new();
+    engine.set_global("name", Value::String("sl".to_string())).unwrap();
+    engine.set_global("count", Value::Int(1)).unwrap();
+    assert_eq!(engine.eval("count <- count + 1; name + \"!\""), Ok(Value::String("sl!".to_string())));
+    assert_eq!(engine.get_global("count"), Some(Value::Int(2)));
+    // a global given again replaces the old one, its type too
+    engine.set_global("count", Value::Bool(true)).unwrap();
+    assert_eq!(engine.eval("not count"), Ok(Value::Bool(false)));
+    assert_eq!(engine.globals(), vec![
+        ("count".to_string(), VarTypes::Bool, Value::Bool(true)),
+        ("name".to_string(), VarTypes::Str, Value::String("sl".to_string()))
+    ]);
+    assert_eq!(engine.get_global("missing"), None);
+
+    assert_eq!(engine.set_global("nothing", Value::Array(vec![])).unwrap_err().kind, ErrorKind::Type);
+    assert_eq!(engine.set_global("small", Value::Sized(300, IntType::U8)).unwrap_err().kind, ErrorKind::Type);
+}
+
+#[test]
+fn type_of_does_not_run() {
+    let (mut engine, output) = engine();
+    engine.eval("int a <- 1; fun show(int x) { out(x); }").unwrap();
+    assert_eq!(engine.type_of("a as float * 2.0 > 1.0"), Ok(Some(VarTypes::Bool)));
+    assert_eq!(engine.type_of("[a]"), Ok(Some(VarTypes::Array(Box::new(VarTypes::Int)))));
+    assert_eq!(engine.type_of("show(a)"), Ok(None));
+    assert_eq!(engine.type_of("a + true").unwrap_err().kind, ErrorKind::Type);
+    assert_eq!(engine.type_of("int b <- 1;").unwrap_err().kind, ErrorKind::Syntax);
+    assert!(output.borrow().is_empty());
+}
+
+#[test]
+fn only_the_given_natives() {
+    let mut engine = Engine::with_natives(Natives::new());
+    assert_eq!(engine.eval("out(1);").unwrap_err().kind, ErrorKind::Name);
+    assert_eq!(engine.eval("1 + 1"), Ok(Value::Int(2)));
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::{engine, error, run};
+use sl::{ErrorKind, Value, CALL_LIMIT};
+
+const SUM: &str = "fun sum(int n) int { if n == 0 { return 0; } return n + sum(n - 1); }";
+
+// debug builds take far more stack for every call than the 2 MB test threads have
+fn on_big_stack<F: FnOnce() + Send + 'static>(test: F) {
+    std::thread::Builder::new().stack_size(64 << 20).spawn(test).unwrap().join().unwrap();
+}
+
+#[test]
+fn call_and_return() {
+    assert_eq!(run(r#"
+        fun add(int a, int b) int {
+            return a + b;
+        }
+        fun greet(str name) {
+            out("hello " + name);
+        }
+        out(add(1, 2) * 3);
+        greet("you");
+    "#), "9\nhello you\n");
+}
+
+#[test]
+fn called_before_definition() {
+    assert_eq!(run("out(twice(4)); fun twice(int a) int { return a * 2; }"), "8\n");
+}
+
+#[test]
+fn return_leaves_loops() {
+    assert_eq!(run(r#"
+        fun first_above(int limit) int {
+            int i <- 0;
+            while true {
+                if i * i > limit {
+                    return i;
+                }
+                i <- i + 1;
+            }
+            return -1;
+        }
+        out(first_above(50));
+    "#), "8\n");
+}
+
+#[test]
+fn functions_only_see_their_arguments() {
+    assert_eq!(error("int outside <- 1; fun peek() int { return outside; }").kind, ErrorKind::Name);
+}
+
+#[test]
+fn arguments_are_checked() {
+    assert_eq!(error("fun f(int a) {} f(1, 2);").kind, ErrorKind::Type);
+    assert_eq!(error(r#"fun f(int a) {} f("one");"#).kind, ErrorKind::Type);
+    assert_eq!(error("fun f() int { return true; }").kind, ErrorKind::Type);
+}
+
+#[test]
+fn missing_return_is_an_error() {
+    let error = error("fun f(int a) int { if a > 0 { return a; } } f(-1)");
+    assert_eq!(error.kind, ErrorKind::Type);
+}
+
+#[test]
+fn deep_recursion() {
+    on_big_stack(|| {
+        let deepest = CALL_LIMIT - 1;
+        assert_eq!(engine().0.eval(&format!("{} sum({})", SUM, deepest)),
+            Ok(Value::Int((deepest * (deepest + 1) / 2) as i128)));
+    });
+}
+
+#[test]
+fn unbounded_recursion_stops() {
+    on_big_stack(|| {
+        let error = error("fun forever(int n) int { return forever(n + 1); }\nforever(0)");
+        assert_eq!(error.kind, ErrorKind::Runtime);
+        // at the call that went too deep
+        assert_eq!((error.span.line, error.span.column), (1, 33));
+    });
+}
+
+#[test]
+fn call_limit_can_be_changed() {
+    let (mut engine, _) = engine();
+    engine.set_call_limit(10);
+    assert_eq!(engine.eval(&format!("{} sum(9)", SUM)), Ok(Value::Int(45)));
+    assert_eq!(engine.eval("sum(10)").unwrap_err().kind, ErrorKind::Runtime);
+    // the depth goes back to zero after the error
+    assert_eq!(engine.eval("sum(9)"), Ok(Value::Int(45)));
+}
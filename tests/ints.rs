@@ -0,0 +1,91 @@
+mod common;
+
+use common::{engine, error, run};
+use sl::{ErrorKind, IntType, Overflow, Value};
+
+#[test]
+fn overflow_modes() {
+    let source = "big + 1";
+    let (mut engine, _) = engine();
+    engine.eval("int big <- 170141183460469231731687303715884105727;").unwrap();
+    let error = engine.eval(source).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Runtime);
+
+    engine.set_overflow(Overflow::Wrapping);
+    assert_eq!(engine.eval(source), Ok(Value::Int(i128::MIN)));
+    engine.set_overflow(Overflow::Saturating);
+    assert_eq!(engine.eval(source), Ok(Value::Int(i128::MAX)));
+
+    engine.set_overflow(Overflow::Wrapping);
+    assert_eq!(engine.eval("u8 small <- 250; small + 10"), Ok(Value::Sized(4, IntType::U8)));
+    engine.set_overflow(Overflow::Saturating);
+    assert_eq!(engine.eval("i8 low <- -120; low - 10"), Ok(Value::Sized(-128, IntType::I8)));
+}
+
+#[test]
+fn division_by_zero() {
+    let (mut engine, _) = engine();
+    let error = engine.eval("int zero <- 0; 1 / zero").unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Runtime);
+}
+
+#[test]
+fn every_operation_is_checked() {
+    let big = "int big <- 170141183460469231731687303715884105727; int small <- -170141183460469231731687303715884105728;";
+    for operation in ["big + 1", "small - 1", "big * 2", "small / -1", "-small"] {
+        let found = error(&format!("{} out({});", big, operation));
+        assert_eq!(found.kind, ErrorKind::Runtime, "{}", operation);
+        assert!(found.message.ends_with("doesn't fit in an int"), "{}", found.message);
+    }
+    // the remainder is 0 even though the division doesn't fit
+    assert_eq!(run(&format!("{} out(small % -1);", big)), "0\n");
+}
+
+#[test]
+fn division_by_zero_is_shown_at_the_operator() {
+    let found = error("int zero <- 0;\nout(5 % zero);");
+    assert_eq!((found.kind, found.span.line, found.span.column), (ErrorKind::Runtime, 2, 7));
+    assert_eq!(found.message, "division by zero");
+    // division by zero stays an error in every overflow mode
+    let (mut engine, _) = engine();
+    engine.set_overflow(Overflow::Wrapping);
+    assert_eq!(engine.eval("int zero <- 0; 1 / zero").unwrap_err().kind, ErrorKind::Runtime);
+}
+
+#[test]
+fn floats_follow_ieee() {
+    assert_eq!(run("out(1.0 / 0.0); out(0.0 / 0.0); out(-1.0 / 0.0);"), "inf\nNaN\n-inf\n");
+}
+
+#[test]
+fn sized_int_coercion() {
+    assert_eq!(run("u8 a <- 200; out(a + 55); out(300 as u8); out(-1 as u16);"), "255\n44\n65535\n");
+    assert_eq!(run("[i16] values <- [1, 2, 3]; values[0] <- 7; out(values);"), "[7, 2, 3]\n");
+    // comparisons work with literals outside the type
+    assert_eq!(run("u8 a <- 1; out(a < 300); out(a == -1);"), "true\nfalse\n");
+
+    let (mut engine, _) = engine();
+    let error = engine.eval("u8 a <- 300;").unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Type);
+    let error = engine.eval("u8 a <- 200; a + 100").unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Runtime);
+    // an int that isn't a literal needs a cast
+    let error = engine.eval("int b <- 1; u8 c <- b;").unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Type);
+}
+
+#[test]
+fn casts() {
+    assert_eq!(run("out(-3.7 as int); out(1000.0 as u8); out((0.0 / 0.0) as int); out((-1.0 / 0.0) as i8);"), "-3\n255\n0\n-128\n");
+    assert_eq!(run("out(3 as float / 2.0); u64 a <- 18446744073709551615; out(a as i64); out(a as int); i32 x <- -5; out(x as u8);"),
+        "1.5\n-1\n18446744073709551615\n251\n");
+    assert_eq!(error("out(\"1\" as int);").kind, ErrorKind::Type);
+    assert_eq!(error("out(true as int);").kind, ErrorKind::Type);
+}
+
+#[test]
+fn sized_ints_only_work_with_their_own_type() {
+    assert_eq!(error("u16 a <- 1; u32 b <- 2; out(a + b);").kind, ErrorKind::Type);
+    assert_eq!(error("i64 a <- 9223372036854775807; out(a + 1);").kind, ErrorKind::Runtime);
+    assert_eq!(run("i32 x <- -5; out(x % 3); u16 a <- 1; u32 b <- 2; out(a as u32 + b);"), "-2\n3\n");
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use common::{error, run};
+use sl::ErrorKind;
+
+#[test]
+fn while_break_and_continue() {
+    assert_eq!(run(r#"
+        int i <- 0;
+        while i < 10 {
+            i <- i + 1;
+            if i == 3 {
+                continue;
+            }
+            if i == 6 {
+                break;
+            }
+            out(i);
+        }
+        out(i);
+    "#), "1\n2\n4\n5\n6\n");
+}
+
+#[test]
+fn break_only_leaves_the_inner_loop() {
+    assert_eq!(run(r#"
+        int outer <- 0;
+        while outer < 3 {
+            outer <- outer + 1;
+            int inner <- 0;
+            while true {
+                inner <- inner + 1;
+                if inner == outer {
+                    break;
+                }
+            }
+            out(inner);
+        }
+    "#), "1\n2\n3\n");
+}
+
+#[test]
+fn while_variables_live_for_one_run() {
+    // `int twice` is declared again on every run of the body
+    assert_eq!(run("int i <- 0; while i < 3 { int twice <- i * 2; i <- i + 1; out(twice); }"), "0\n2\n4\n");
+    assert_eq!(error("int i <- 0; while i < 3 { int inside <- 1; i <- i + 1; } out(inside);").kind, ErrorKind::Name);
+}
+
+#[test]
+fn break_outside_a_loop() {
+    assert_eq!(error("break;").kind, ErrorKind::Syntax);
+    assert_eq!(error("int a <- 1; continue;").kind, ErrorKind::Syntax);
+}
+
+#[test]
+fn while_needs_a_bool() {
+    assert_eq!(error("while 1 { }").kind, ErrorKind::Type);
+}
+
+#[test]
+fn for_counts_up_to_the_end() {
+    assert_eq!(run("for i in 0..3 { out(i); }"), "0\n1\n2\n");
+    assert_eq!(run("for i in -2..0 { out(i); }"), "-2\n-1\n");
+    assert_eq!(run("for i in 0..7 step 3 { out(i); }"), "0\n3\n6\n");
+    assert_eq!(run(r#"for i in 5..2 { out(i); } out("none");"#), "none\n");
+    assert_eq!(run("for i in 0..5 { if i == 1 { continue; } if i == 3 { break; } out(i); }"), "0\n2\n");
+}
+
+#[test]
+fn for_range_is_worked_out_once() {
+    assert_eq!(run("int end <- 3; for i in 0..end { end <- 1; out(i); }"), "0\n1\n2\n");
+}
+
+#[test]
+fn for_variable_only_lives_in_the_body() {
+    assert_eq!(run("for i in 0..2 { } for i in 5..6 { out(i); }"), "5\n");
+    assert_eq!(error("for i in 0..3 { } out(i);").kind, ErrorKind::Name);
+    assert_eq!(error("int i <- 5; for i in 0..2 { }").kind, ErrorKind::Name);
+}
+
+#[test]
+fn for_needs_ints_and_a_positive_step() {
+    assert_eq!(error("for i in 0.0..3 { }").kind, ErrorKind::Type);
+    assert_eq!(error("for i in 0..10 step 0 { }").kind, ErrorKind::Runtime);
+    assert_eq!(error("int by <- -1; for i in 0..10 step by { }").kind, ErrorKind::Runtime);
+}
@@ -0,0 +1,108 @@
+// diagnostics are only built once something went wrong, so their size doesn't matter
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use common::engine;
+use sl::{native_error, ArgTypes, ErrorKind, IntType, Value, VarTypes};
+
+#[test]
+fn natives_are_called_with_their_arguments() {
+    let (mut engine, _) = engine();
+    engine.register_function("twice", vec![ArgTypes::Int], Some(VarTypes::Int), |_, arguments| match arguments[0] {
+        Value::Int(value) => Ok(Value::Int(value * 2)),
+        _ => unreachable!()
+    });
+    engine.register_function("join", vec![ArgTypes::Array(Box::new(ArgTypes::Str)), ArgTypes::Str], Some(VarTypes::Str), |_, arguments| {
+        match (&arguments[0], &arguments[1]) {
+            (Value::Array(parts), Value::String(separator)) => {
+                let parts: Vec<String> = parts.iter().map(|part| part.to_string()).collect();
+                Ok(Value::String(parts.join(separator)))
+            },
+            _ => unreachable!()
+        }
+    });
+    assert_eq!(engine.eval("twice(twice(3)) + 1"), Ok(Value::Int(13)));
+    assert_eq!(engine.eval(r#"join(["a", "b", "c"], ", ")"#), Ok(Value::String("a, b, c".to_string())));
+    // the checker knows the signature like it knows one from `fun`
+    assert_eq!(engine.eval("twice(\"a\")").unwrap_err().kind, ErrorKind::Type);
+    assert_eq!(engine.eval("twice(1, 2)").unwrap_err().kind, ErrorKind::Type);
+    assert_eq!(engine.eval("str s <- twice(1);").unwrap_err().kind, ErrorKind::Type);
+}
+
+#[test]
+fn natives_without_a_return_type() {
+    let (mut engine, _) = engine();
+    let calls = Rc::new(Cell::new(0));
+    let counted = calls.clone();
+    engine.register_function("count", vec![], None, move |_, _| {
+        counted.set(counted.get() + 1);
+        Ok(Value::Void)
+    });
+    assert_eq!(engine.eval("for i in 0..3 { count(); }"), Ok(Value::Void));
+    assert_eq!(calls.get(), 3);
+    assert_eq!(engine.eval("int a <- count();").unwrap_err().kind, ErrorKind::Type);
+}
+
+#[test]
+fn arguments_take_the_declared_type() {
+    let (mut engine, _) = engine();
+    engine.register_function("byte", vec![ArgTypes::Sized(IntType::U8)], Some(VarTypes::Sized(IntType::U8)), |_, arguments| {
+        Ok(arguments[0].clone())
+    });
+    assert_eq!(engine.eval("byte(200)"), Ok(Value::Sized(200, IntType::U8)));
+    assert_eq!(engine.eval("byte(300)").unwrap_err().kind, ErrorKind::Type);
+}
+
+#[test]
+fn variadic_natives() {
+    let (mut engine, _) = engine();
+    engine.register_function("sum", vec![ArgTypes::Str, ArgTypes::Variadic(Box::new(ArgTypes::Int))], Some(VarTypes::Str), |_, arguments| {
+        let total: i128 = arguments[1..].iter().map(|argument| match argument {
+            Value::Int(value) => *value,
+            _ => unreachable!()
+        }).sum();
+        Ok(Value::String(format!("{}{}", arguments[0], total)))
+    });
+    assert_eq!(engine.eval(r#"sum("total ")"#), Ok(Value::String("total 0".to_string())));
+    assert_eq!(engine.eval(r#"sum("total ", 1, 2, 3)"#), Ok(Value::String("total 6".to_string())));
+    assert_eq!(engine.eval("sum()").unwrap_err().kind, ErrorKind::Type);
+    assert_eq!(engine.eval(r#"sum("a", 1, "b")"#).unwrap_err().kind, ErrorKind::Type);
+}
+
+#[test]
+fn native_errors_are_shown_at_the_call() {
+    let (mut engine, _) = engine();
+    engine.register_function("fail", vec![ArgTypes::Str], None, |_, arguments| {
+        Err(native_error(format!("failed with {}", arguments[0])).with_note("on purpose".to_string()))
+    });
+    let found = engine.eval("int a <- 1;\n  fail(\"x\");").unwrap_err();
+    assert_eq!(found.kind, ErrorKind::Runtime);
+    assert_eq!(found.message, "failed with x");
+    assert_eq!((found.span.line, found.span.column), (2, 3));
+    assert_eq!(found.notes, vec!["on purpose".to_string()]);
+}
+
+#[test]
+fn natives_have_to_return_their_type() {
+    let (mut engine, _) = engine();
+    engine.register_function("lie", vec![], Some(VarTypes::Int), |_, _| Ok(Value::String("no".to_string())));
+    engine.register_function("chatty", vec![], None, |_, _| Ok(Value::Int(1)));
+    assert_eq!(engine.eval("lie()").unwrap_err().kind, ErrorKind::Runtime);
+    assert_eq!(engine.eval("chatty();").unwrap_err().kind, ErrorKind::Runtime);
+}
+
+#[test]
+fn natives_replace_builtins() {
+    let (mut engine, output) = engine();
+    engine.register_function("out", vec![ArgTypes::Int], None, |context, arguments| {
+        writeln!(context.output(), "<{}>", arguments[0]).unwrap();
+        Ok(Value::Void)
+    });
+    engine.eval("out(1);").unwrap();
+    assert_eq!(output.borrow().as_slice(), b"<1>\n");
+    assert_eq!(engine.eval("out(\"a\");").unwrap_err().kind, ErrorKind::Type);
+}
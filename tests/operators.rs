@@ -0,0 +1,76 @@
+mod common;
+
+use common::{engine, error, run};
+use sl::{ErrorKind, Overflow, Value};
+
+#[test]
+fn and_or_not() {
+    assert_eq!(run("out(true and false); out(true or false); out(not true); out(not not true);"), "false\ntrue\nfalse\ntrue\n");
+    assert_eq!(run("out(1 < 2 and not 2 < 1 or false); out(false and true or true);"), "true\ntrue\n");
+}
+
+#[test]
+fn and_or_only_work_out_the_right_side_when_needed() {
+    let loud = r#"fun loud(bool v) bool { out("ran"); return v; } "#;
+    assert_eq!(run(&format!("{} out(false and loud(true)); out(true or loud(false));", loud)), "false\ntrue\n");
+    assert_eq!(run(&format!("{} out(true and loud(false)); out(false or loud(true));", loud)), "ran\nfalse\nran\ntrue\n");
+    assert_eq!(run(r#"int zero <- 0; if zero != 0 and 10 / zero > 1 { out("no"); } else { out("safe"); }"#), "safe\n");
+}
+
+#[test]
+fn logic_needs_bools() {
+    assert_eq!(error("out(1 and true);").kind, ErrorKind::Type);
+    assert_eq!(error("out(false or \"a\");").kind, ErrorKind::Type);
+    assert_eq!(error("out(not 1);").kind, ErrorKind::Type);
+}
+
+#[test]
+fn unary_minus_and_plus() {
+    assert_eq!(run("out(-5); out(-(2 + 3) * 2); out(+3); out(-2.5); out(- -4);"), "-5\n-10\n3\n-2.5\n4\n");
+    assert_eq!(run("int a <- 3; out(-a); out(1 - -a); out(1--1); bigint b <- 5; out(-b);"), "-3\n4\n2\n-5\n");
+    assert_eq!(run("int a <- 2; out(-a ** 2); out((-a) ** 2);"), "-4\n4\n");
+}
+
+#[test]
+fn negative_literals_reach_the_smallest_int() {
+    assert_eq!(run("out(-170141183460469231731687303715884105728);"), "-170141183460469231731687303715884105728\n");
+    assert_eq!(error("int m <- -170141183460469231731687303715884105728; out(-m);").kind, ErrorKind::Runtime);
+    assert_eq!(error("i8 a <- -128; out(-a);").kind, ErrorKind::Runtime);
+    assert_eq!(error("u8 a <- 1; out(-a);").kind, ErrorKind::Runtime);
+}
+
+#[test]
+fn unary_needs_numbers() {
+    for source in ["out(-true);", "out(-\"a\");", "out(+\"a\");", "out(-'a');"] {
+        assert_eq!(error(source).kind, ErrorKind::Type, "{}", source);
+    }
+}
+
+#[test]
+fn power() {
+    assert_eq!(run("out(2 ** 10); out(2 ** 3 ** 2); out(0 ** 0); out((-3) ** 3); out(10 ** 0);"), "1024\n512\n1\n-27\n1\n");
+    // a float on either side gives a float
+    assert_eq!(run("out(2 ** 0.5); out(2.0 ** -1); out(4.0 ** 2);"), "1.4142135623730951\n0.5\n16\n");
+    assert_eq!(run("u8 a <- 2; out(a ** 7);"), "128\n");
+}
+
+#[test]
+fn power_errors() {
+    assert_eq!(error("int e <- -1; out(2 ** e);").kind, ErrorKind::Runtime);
+    assert_eq!(error("out(2 ** 200);").kind, ErrorKind::Runtime);
+    assert_eq!(error("u8 a <- 2; out(a ** 8);").kind, ErrorKind::Runtime);
+    assert_eq!(error("out(\"a\" ** 2);").kind, ErrorKind::Type);
+}
+
+#[test]
+fn power_follows_the_overflow_mode() {
+    let (mut engine, _) = engine();
+    engine.eval("int two <- 2; int three <- 3;").unwrap();
+    engine.set_overflow(Overflow::Wrapping);
+    assert_eq!(engine.eval("two ** 127"), Ok(Value::Int(i128::MIN)));
+    assert_eq!(engine.eval("two ** 200"), Ok(Value::Int(0)));
+    assert_eq!(engine.eval("three ** 81"), Ok(Value::Int(103144121322099306484875023187381681347)));
+    engine.set_overflow(Overflow::Saturating);
+    assert_eq!(engine.eval("two ** 200"), Ok(Value::Int(i128::MAX)));
+    assert_eq!(engine.eval("(0 - three) ** 81"), Ok(Value::Int(i128::MIN)));
+}
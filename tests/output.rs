@@ -0,0 +1,75 @@
+// diagnostics are only built once something went wrong, so their size doesn't matter
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use std::cell::RefCell;
+use std::io::{Cursor, Write};
+use std::rc::Rc;
+
+use common::{engine, run};
+use sl::{native_error, ArgTypes, ErrorKind, Value, VarTypes};
+
+#[test]
+fn output_is_captured() {
+    assert_eq!(run(r#"out("hello"); out(1 + 2);"#), "hello\n3\n");
+    assert_eq!(run(r#"out([1, 2]); out(true); out(1.5); out('c');"#), "[1, 2]\ntrue\n1.5\nc\n");
+}
+
+#[test]
+fn output_can_be_changed_between_evals() {
+    let (mut engine, first) = engine();
+    engine.eval("out(1);").unwrap();
+    let second = Rc::new(RefCell::new(Vec::new()));
+    engine.set_output(second.clone());
+    engine.eval("out(2);").unwrap();
+    assert_eq!(first.borrow().as_slice(), b"1\n");
+    assert_eq!(second.borrow().as_slice(), b"2\n");
+}
+
+#[test]
+fn output_of_a_failed_eval_stays() {
+    let (mut engine, output) = engine();
+    assert!(engine.eval("out(1); int zero <- 0; out(1 / zero);").is_err());
+    assert_eq!(output.borrow().as_slice(), b"1\n");
+}
+
+struct Broken;
+
+impl Write for Broken {
+    fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::other("the disk is gone"))
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn output_that_fails_is_a_runtime_error() {
+    let (mut engine, _) = engine();
+    engine.set_output(Rc::new(RefCell::new(Broken)));
+    let found = engine.eval("out(1);").unwrap_err();
+    assert_eq!(found.kind, ErrorKind::Runtime);
+    assert!(found.message.contains("the disk is gone"), "{}", found.message);
+}
+
+#[test]
+fn natives_reach_every_stream() {
+    let (mut engine, output) = engine();
+    let errors = Rc::new(RefCell::new(Vec::new()));
+    engine.set_error_output(errors.clone());
+    engine.set_input(Rc::new(RefCell::new(Cursor::new("first\nsecond\n"))));
+    engine.register_function("warn", vec![ArgTypes::Str], None, |context, arguments| {
+        writeln!(context.error_output(), "warning: {}", arguments[0]).map_err(|error| native_error(error.to_string()))?;
+        Ok(Value::Void)
+    });
+    engine.register_function("read_line", vec![], Some(VarTypes::Str), |context, _| {
+        let mut line = String::new();
+        context.input().read_line(&mut line).map_err(|error| native_error(error.to_string()))?;
+        Ok(Value::String(line.trim_end().to_string()))
+    });
+    engine.eval(r#"warn("careful"); out(read_line()); out(read_line()); out(read_line() == "");"#).unwrap();
+    assert_eq!(output.borrow().as_slice(), b"first\nsecond\ntrue\n");
+    assert_eq!(errors.borrow().as_slice(), b"warning: careful\n");
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::sl;
+
+// what the repl prints for the given lines, stdout and stderr
+fn repl(input: &str) -> (String, String) {
+    let (code, output, errors) = sl(&["repl"], input);
+    assert_eq!(code, 0, "{}", errors);
+    (output, errors)
+}
+
+#[test]
+fn statements_and_an_expression_on_one_line() {
+    let (output, errors) = repl("int a <- 1; a + 1\na * 10\n");
+    assert_eq!(output, "2\n10\n");
+    assert_eq!(errors, "");
+}
+
+#[test]
+fn failed_inputs_roll_back() {
+    let (output, errors) = repl("int a <- 1;\na <- 2; int b <- 0; a / b\na\nint b <- 5; b\n");
+    assert_eq!(output, "1\n5\n");
+    assert!(errors.contains("error"), "{}", errors);
+}
+
+#[test]
+fn blocks_and_strings_go_over_several_lines() {
+    let (output, errors) = repl("fun double(int x) int {\n    return x * 2;\n}\ndouble(4)\nstr s <- \"a\nb\";\ns\nchar c <- '{';\nc\n");
+    assert_eq!(output, "8\n\"a\\nb\"\n{\n");
+    assert_eq!(errors, "");
+}
+
+#[test]
+fn values_are_printed_by_type() {
+    let (output, _) = repl("\"text\"\n1.5\ntrue\n[1, 2]\nout(\"printed\");\n");
+    // strings get quotes, `out` gives nothing so only what it wrote is there
+    assert_eq!(output, "\"text\"\n1.5\ntrue\n[1, 2]\nprinted\n");
+}
+
+#[test]
+fn exit_only_stops_the_input() {
+    let (output, errors) = repl("int a <- 1;\na <- 2; exit(3); out(\"skipped\");\na\n");
+    assert_eq!(output, "2\n");
+    assert_eq!(errors, "");
+}
+
+#[test]
+fn commands() {
+    let (output, errors) = repl(":type 1 + 2 > 3\n:type out(1)\nint b <- 2;\nint a <- 1;\n:vars\n:reset\n:vars\n:help\n");
+    assert!(output.starts_with("Bool\nno value\na: Int = 1\nb: Int = 2\ntype statements"), "{}", output);
+    assert_eq!(errors, "");
+
+    let (output, errors) = repl(":type nope\n:bogus\n");
+    assert_eq!(output, "");
+    assert!(errors.contains("variable 'nope' does not exists"), "{}", errors);
+    assert!(errors.contains("unknown command ':bogus', see :help"), "{}", errors);
+}
+
+#[test]
+fn quit_stops_reading() {
+    assert_eq!(repl("1\n:quit\n2\n").0, "1\n");
+    assert_eq!(repl("1\n:q\n2\n").0, "1\n");
+}
+
+#[test]
+fn load_keeps_what_the_file_defines() {
+    let path = std::env::temp_dir().join(format!("sl-repl-load-{}.sl", std::process::id()));
+    std::fs::write(&path, "fun triple(int x) int { return x * 3; } out(\"loaded\");").unwrap();
+    let (output, errors) = repl(&format!(":load {}\ntriple(2)\n:load /no/such/file.sl\n", path.display()));
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(output, "loaded\n6\n");
+    assert!(errors.starts_with("io error: couldn't read '/no/such/file.sl'"), "{}", errors);
+}
@@ -0,0 +1,27 @@
+mod common;
+
+use common::{error, run};
+use sl::ErrorKind;
+
+#[test]
+fn expressions_in_strings() {
+    assert_eq!(run(r#"int count <- 3; out("total: {count * 2}!");"#), "total: 6!\n");
+    assert_eq!(run(r#"out("{1} {2.5} {true} {"in"} {'c'}");"#), "1 2.5 true in c\n");
+    assert_eq!(run(r#"fun next(int x) int { return x + 1; } out("{next(1)}{next(2)}");"#), "23\n");
+    assert_eq!(run(r#"str s <- "x"; out("{s}{s}" == "xx");"#), "true\n");
+}
+
+#[test]
+fn braces_that_are_not_expressions() {
+    assert_eq!(run(r#"out("a \{b\}"); out(r"{x}");"#), "a {b}\n{x}\n");
+}
+
+#[test]
+fn errors_point_into_the_string() {
+    let found = error(r#"out("ok {nope}");"#);
+    assert_eq!((found.kind, found.span.column), (ErrorKind::Name, 10));
+    assert_eq!(error(r#"out("{}");"#).kind, ErrorKind::Syntax);
+    assert_eq!(error(r#"out("{1 +}");"#).kind, ErrorKind::Syntax);
+    assert_eq!(error(r#"out("{1");"#).kind, ErrorKind::Syntax);
+    assert_eq!(error(r#"[int] a <- [1]; out("{a}");"#).kind, ErrorKind::Type);
+}
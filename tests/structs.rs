@@ -0,0 +1,129 @@
+mod common;
+
+use common::{engine, error, run};
+use sl::ErrorKind;
+
+#[test]
+fn fields_are_read_and_changed() {
+    assert_eq!(run(r#"
+        struct Point { int x; int y; }
+        struct Line { Point start; Point end; }
+        Point p <- Point(1, 2);
+        p.x <- 3;
+        Line l <- Line(p, Point(5, 6));
+        l.end.y <- 7;
+        out(l);
+        out(l.start.x + l.end.y);
+    "#), "Line { start: Point { x: 3, y: 2 }, end: Point { x: 5, y: 7 } }\n10\n");
+}
+
+#[test]
+fn structs_are_copied() {
+    assert_eq!(run(r#"
+        struct P { int x; }
+        fun change(P p) P {
+            p.x <- 2;
+            return p;
+        }
+        P a <- P(1);
+        P b <- a;
+        b.x <- 5;
+        P c <- change(a);
+        out(a.x);
+        out(b.x);
+        out(c.x);
+    "#), "1\n5\n2\n");
+}
+
+#[test]
+fn structs_in_arrays() {
+    assert_eq!(run("struct P { int x; } [P] ps <- [P(1), P(2)]; ps[1].x <- 7; out(ps); out(ps[1].x);"),
+        "[P { x: 1 }, P { x: 7 }]\n7\n");
+}
+
+#[test]
+fn structs_are_compared_by_fields() {
+    assert_eq!(run("struct P { int x; int y; } P p <- P(1, 2); out(p == P(1, 2)); out(p != P(1, 3));"), "true\ntrue\n");
+}
+
+#[test]
+fn structs_can_be_used_before_their_definition() {
+    assert_eq!(run("P p <- P(1); struct P { int x; } out(p.x);"), "1\n");
+}
+
+#[test]
+fn struct_errors() {
+    assert_eq!(error("struct P { int x; } P p <- P(\"a\");").kind, ErrorKind::Type);
+    assert_eq!(error("struct P { int x; } P p <- P(1, 2);").kind, ErrorKind::Type);
+    assert_eq!(error("struct P { int x; } P p <- P(1); out(p.z);").kind, ErrorKind::Name);
+    assert_eq!(error("struct P { int x; } struct P { int y; }").kind, ErrorKind::Name);
+    assert_eq!(error("struct P { int x; int x; }").kind, ErrorKind::Name);
+    assert_eq!(error("struct S { nope x; }").kind, ErrorKind::Name);
+    // the same fields don't make the same type
+    assert_eq!(error("struct A { int x; } struct B { int x; } A a <- B(1);").kind, ErrorKind::Type);
+}
+
+#[test]
+fn recursive_structs() {
+    let (mut engine, _) = engine();
+    let error = engine.eval("struct A { B b; } struct B { int x; A a; }").unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Type);
+    assert_eq!(run("struct Tree { int value; [Tree] children; } out(Tree(1, [Tree(2, [])]));"),
+        "Tree { value: 1, children: [Tree { value: 2, children: [] }] }\n");
+}
+
+const POINT: &str = r#"
+    struct Point { int x; int y; }
+    impl Point {
+        fun len2(self) int {
+            return self.x * self.x + self.y * self.y;
+        }
+        fun move(self, int by) {
+            self.x <- self.x + by;
+        }
+    }
+    // a struct can have more than one impl block
+    impl Point {
+        fun new(int x, int y) Point {
+            return Point(x, y);
+        }
+    }
+"#;
+
+#[test]
+fn methods_and_struct_functions() {
+    assert_eq!(run(&format!("{} Point p <- Point.new(1, 2); p.move(2); out(p.len2()); out(p); out(Point.new(1, 2).len2());", POINT)),
+        "13\nPoint { x: 3, y: 2 }\n5\n");
+}
+
+#[test]
+fn methods_change_what_they_were_called_on() {
+    assert_eq!(run(&format!(r#"{}
+        [Point] ps <- [Point(0, 0)];
+        ps[0].move(3);
+        out(ps);
+        struct Holder {{ Point inner; }}
+        Holder h <- Holder(Point(1, 1));
+        h.inner.move(1);
+        out(h);
+    "#, POINT)), "[Point { x: 3, y: 0 }]\nHolder { inner: Point { x: 2, y: 1 } }\n");
+    // an argument is a copy, the caller's value stays the same
+    assert_eq!(run(&format!("{} fun f(Point p) int {{ p.move(5); return p.x; }} Point p <- Point(1, 1); out(f(p)); out(p.x);", POINT)),
+        "6\n1\n");
+}
+
+#[test]
+fn method_errors() {
+    let (mut engine, _) = engine();
+    engine.eval(POINT).unwrap();
+    let kind = |engine: &mut sl::Engine, source: &str| engine.eval(source).unwrap_err().kind;
+    assert_eq!(kind(&mut engine, "Point.len2();"), ErrorKind::Type);
+    assert_eq!(kind(&mut engine, "Point p <- Point(1, 1); p.new(1, 2);"), ErrorKind::Type);
+    assert_eq!(kind(&mut engine, "Point p <- Point(1, 1); p.nope();"), ErrorKind::Name);
+    assert_eq!(kind(&mut engine, "Point p <- Point(1, 1); p.move(\"a\");"), ErrorKind::Type);
+    assert_eq!(kind(&mut engine, "int a <- 1; a.len2();"), ErrorKind::Type);
+    // a variable named like the struct hides its functions
+    assert_eq!(kind(&mut engine, "int Point <- 1; out(Point.new(1, 2));"), ErrorKind::Type);
+    assert_eq!(kind(&mut engine, "impl Point { fun move(self) { } }"), ErrorKind::Name);
+    assert_eq!(kind(&mut engine, "impl Nope { fun f() { } }"), ErrorKind::Name);
+}